@@ -13,58 +13,63 @@ use criterion::{
 };
 use mucodec::*;
 use proptest::test_runner::{RngAlgorithm, TestRng};
+use rand::RngCore;
 
 #[inline(always)]
 fn bench_pack_unpack(c: &mut Criterion<WallTime>) {
     let mut rng = TestRng::from_seed(RngAlgorithm::ChaCha, &[42u8; 32]);
 
-    fn bench_list<const N: usize, const BYTES: usize, L: ReprBytes<BYTES>>(
-        c: &mut Criterion<WallTime>,
-        _rng: &mut TestRng,
-    ) where
-        L: ReprPacked + Clone + 'static,
-    {
-        let input = L::zero();
-        let (bit_width, packed_data) = input.pack();
+    // Benchmarks every list on random values, which only the plain mode packs well, and on the
+    // same values sorted, which the delta modes pack into fewer bits.
+    macro_rules! bench_list {
+        ($type:ty, $size:expr) => {{
+            let random = (0..$size)
+                .map(|_| rng.next_u64() as $type)
+                .collect::<List<$type, $size>>();
+            let mut sorted = random.into_array();
+            sorted.sort();
+            let sorted = List::from(sorted);
 
-        let mut group = c.benchmark_group(format!("Pack/Unpack List<{N}>"));
-        group.throughput(Throughput::Bytes(N as u64));
+            let mut group = c.benchmark_group(format!("List<{}, {}>", stringify!($type), $size));
+            group.throughput(Throughput::Bytes(
+                ($size * core::mem::size_of::<$type>()) as u64,
+            ));
 
-        group.bench_with_input(BenchmarkId::new("pack", N), &input, |b, i| {
-            b.iter(|| i.pack());
-        });
+            for (name, input) in [("random", random), ("sorted", sorted)] {
+                let (header, packed) = input.pack();
 
-        group.bench_with_input(
-            BenchmarkId::new("unpack", N),
-            &(bit_width, packed_data.clone()),
-            |b, (width, data)| {
-                b.iter(|| L::unpack(*width, data));
-            },
-        );
+                group.bench_with_input(BenchmarkId::new("pack", name), &input, |b, i| {
+                    b.iter(|| i.pack());
+                });
 
-        group.finish();
+                group.bench_with_input(BenchmarkId::new("pack_plain", name), &input, |b, i| {
+                    b.iter(|| i.pack_with(PackMode::Plain));
+                });
+
+                group.bench_with_input(
+                    BenchmarkId::new("unpack", name),
+                    &(header, packed),
+                    |b, (header, data)| {
+                        b.iter(|| List::<$type, $size>::unpack(*header, data));
+                    },
+                );
+            }
+
+            group.finish();
+        }};
     }
 
-    // For ListU16, each element is 2 bytes + 1 byte for bit width
-    bench_list::<64, { 64 * 2 + 1 }, ListU16<64>>(c, &mut rng);
-    bench_list::<128, { 128 * 2 + 1 }, ListU16<128>>(c, &mut rng);
-    bench_list::<256, { 256 * 2 + 1 }, ListU16<256>>(c, &mut rng);
-    bench_list::<512, { 512 * 2 + 1 }, ListU16<512>>(c, &mut rng);
-    bench_list::<1024, { 1024 * 2 + 1 }, ListU16<1024>>(c, &mut rng);
+    bench_list!(u16, 64);
+    bench_list!(u16, 256);
+    bench_list!(u16, 1024);
 
-    // For ListU32, each element is 4 bytes + 1 byte for bit width
-    bench_list::<64, { 64 * 4 + 1 }, ListU32<64>>(c, &mut rng);
-    bench_list::<128, { 128 * 4 + 1 }, ListU32<128>>(c, &mut rng);
-    bench_list::<256, { 256 * 4 + 1 }, ListU32<256>>(c, &mut rng);
-    bench_list::<512, { 512 * 4 + 1 }, ListU32<512>>(c, &mut rng);
-    bench_list::<1024, { 1024 * 4 + 1 }, ListU32<1024>>(c, &mut rng);
+    bench_list!(u32, 64);
+    bench_list!(u32, 256);
+    bench_list!(u32, 1024);
 
-    // For ListU64, each element is 8 bytes + 1 byte for bit width
-    bench_list::<64, { 64 * 8 + 1 }, ListU64<64>>(c, &mut rng);
-    bench_list::<128, { 128 * 8 + 1 }, ListU64<128>>(c, &mut rng);
-    bench_list::<256, { 256 * 8 + 1 }, ListU64<256>>(c, &mut rng);
-    bench_list::<512, { 512 * 8 + 1 }, ListU64<512>>(c, &mut rng);
-    bench_list::<1024, { 1024 * 8 + 1 }, ListU64<1024>>(c, &mut rng);
+    bench_list!(u64, 64);
+    bench_list!(u64, 256);
+    bench_list!(u64, 1024);
 }
 
 criterion_group!(
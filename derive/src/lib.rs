@@ -1,17 +1,31 @@
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{parse_macro_input, punctuated::Punctuated, Data, DeriveInput, Fields, Type};
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input,
+    punctuated::Punctuated,
+    Attribute,
+    Data,
+    DataEnum,
+    DataStruct,
+    DeriveInput,
+    Fields,
+    Type,
+};
 
-type FieldInfo = Vec<(syn::Member, Type, usize)>;
+type FieldInfo = Vec<(syn::Member, Type)>;
+type GenericParams<'a> = [(
+    syn::Ident,
+    &'a Punctuated<syn::TypeParamBound, syn::Token![+]>,
+)];
 
-#[proc_macro_derive(ReprBytes)]
+#[proc_macro_derive(ReprBytes, attributes(mucodec))]
 pub fn derive_encode(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    let name = &input.ident;
-    let generics = &input.generics;
 
     // Extract generic parameters and their bounds
-    let generic_params = generics
+    let generic_params = input
+        .generics
         .params
         .iter()
         .filter_map(|param| {
@@ -23,205 +37,1425 @@ pub fn derive_encode(input: TokenStream) -> TokenStream {
         })
         .collect::<Vec<_>>();
 
-    let (total_size, field_info) = match get_field_info(&input.data, &generic_params) {
-        Ok(info) => info,
-        Err(e) => return e.to_compile_error().into(),
+    let expanded = match &input.data {
+        Data::Struct(data) => expand_struct(&input, data, &generic_params),
+        Data::Enum(data) => expand_enum(&input, data, &generic_params),
+        Data::Union(_) => Err(syn::Error::new(
+            Span::call_site(),
+            "ReprBytes can only be derived for structs and enums",
+        )),
+    };
+
+    match expanded {
+        Ok(expanded) => TokenStream::from(expanded),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+#[proc_macro_derive(ReprPacked, attributes(mucodec))]
+pub fn derive_packed(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let expanded = match &input.data {
+        Data::Struct(data) => expand_packed_struct(&input, data),
+        Data::Enum(_) | Data::Union(_) => Err(syn::Error::new(
+            Span::call_site(),
+            "ReprPacked can only be derived for structs",
+        )),
+    };
+
+    match expanded {
+        Ok(expanded) => TokenStream::from(expanded),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+#[proc_macro_derive(ReprFlags, attributes(mucodec))]
+pub fn derive_flags(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let expanded = match &input.data {
+        Data::Enum(data) => expand_flags(&input, data),
+        Data::Struct(_) | Data::Union(_) => Err(syn::Error::new(
+            Span::call_site(),
+            "ReprFlags can only be derived for enums",
+        )),
     };
 
+    match expanded {
+        Ok(expanded) => TokenStream::from(expanded),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// A run of bytes in the encoding of a struct.
+enum Segment {
+    /// A field encoded with its own `ReprBytes` implementation, or with the module set by
+    /// `#[mucodec(with = path)]`.
+    Field {
+        name: syn::Member,
+        ty: Box<Type>,
+        size: TokenStream2,
+        binding: syn::Ident,
+        with: Option<syn::Path>,
+        /// Set when `ty` is the field type wrapped in `Be`, for big endian integers.
+        wrapped: bool,
+    },
+    /// Consecutive fields with `#[mucodec(bits = N)]`, packed least significant bit first into
+    /// shared bytes, with signed fields in two's complement. The bits left over in the last byte
+    /// are reserved and must be zero.
+    Bits {
+        fields: Vec<(syn::Member, Type, syn::LitInt, syn::Ident)>,
+        size: usize,
+    },
+    /// Bytes set with `#[mucodec(reserved = N)]`, which are always encoded as zeros. Only strict
+    /// decoding rejects other values, so that older readers accept data from newer writers that
+    /// have put fields in them. With `#[mucodec(layout = "c")]`, alignment padding is reserved
+    /// the same way.
+    Reserved(TokenStream2),
+}
+
+fn expand_struct(
+    input: &DeriveInput,
+    data: &DataStruct,
+    generic_params: &GenericParams,
+) -> Result<TokenStream2, syn::Error> {
+    let name = &input.ident;
+
+    let mut reserved = 0usize;
+    let mut validate = None;
+    let mut endian = None;
+    let mut c_layout = false;
+    parse_attributes(&input.attrs, |meta| {
+        if meta.path.is_ident("layout") {
+            let value = meta.value()?.parse::<syn::LitStr>()?;
+            c_layout = match value.value().as_str() {
+                "c" => true,
+                "packed" => false,
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        value,
+                        "layout must be \"c\" or \"packed\"",
+                    ))
+                }
+            };
+            Ok(())
+        } else if meta.path.is_ident("reserved") {
+            reserved = meta.value()?.parse::<syn::LitInt>()?.base10_parse()?;
+            Ok(())
+        } else if meta.path.is_ident("endian") {
+            endian = Some(Endian::parse(&meta)?);
+            Ok(())
+        } else if meta.path.is_ident("validate") {
+            validate = Some(meta.value()?.parse::<syn::Path>()?);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported mucodec attribute"))
+        }
+    })?;
+
     let mut field_names = Vec::new();
-    let mut field_types = Vec::new();
-    let mut field_sizes = Vec::new();
+    let mut field_bindings = Vec::new();
+    let mut segments = Vec::new();
+    let mut skipped = Vec::new();
+    let mut validated = Vec::new();
+
+    for (i, ((field_name, field_type), field)) in get_field_info(&data.fields)
+        .into_iter()
+        .zip(&data.fields)
+        .enumerate()
+    {
+        let binding = format_ident!("__field{}", i);
+        let attrs = get_field_attrs(field)?;
+
+        if let Some(size) = attrs.reserved {
+            segments.push(Segment::Reserved(quote!(#size)));
+        }
+        if let Some(path) = &attrs.validate {
+            validated.push((field_name.clone(), path.clone()));
+        }
+
+        if attrs.skip {
+            skipped.push((field_name.clone(), binding.clone(), attrs.default_value()));
+            field_names.push(field_name);
+            field_bindings.push(binding);
+            continue;
+        }
+
+        match attrs.bits {
+            Some(bits) if c_layout => {
+                return Err(syn::Error::new_spanned(
+                    bits,
+                    "bitfields are not supported with layout = \"c\"",
+                ))
+            }
+            Some(bits) => {
+                let field = (field_name.clone(), field_type, bits, binding.clone());
+                match segments.last_mut() {
+                    Some(Segment::Bits { fields, .. }) => fields.push(field),
+                    _ => segments.push(Segment::Bits {
+                        fields: vec![field],
+                        size: 0,
+                    }),
+                }
+            }
+            None => {
+                // The struct's byte order only applies to integers, other types have their own
+                let endian = match (attrs.endian, endian) {
+                    (Some(endian), _) => endian,
+                    (None, None) => Endian::Little,
+                    (None, Some(endian)) => match is_integer(&field_type) {
+                        Some(true) => endian,
+                        Some(false) => Endian::Little,
+                        None => {
+                            return Err(syn::Error::new_spanned(
+                                &field_type,
+                                "cannot tell whether this type is an integer that the struct's \
+                                 byte order applies to, set `#[mucodec(endian = \"big\")]` or \
+                                 `#[mucodec(endian = \"little\")]` on the field",
+                            ))
+                        }
+                    },
+                };
+                let wrapped = endian == Endian::Big;
+                let field_type = match wrapped {
+                    true => syn::parse_quote!(::mucodec::Be<#field_type>),
+                    false => field_type,
+                };
 
-    for (name, ty, size) in field_info {
-        field_names.push(name);
-        field_types.push(ty);
-        field_sizes.push(size);
+                segments.push(Segment::Field {
+                    name: field_name.clone(),
+                    size: match &attrs.with {
+                        Some(with) => quote!(#with::SIZE),
+                        None => get_field_size(&field_type, generic_params),
+                    },
+                    ty: Box::new(field_type),
+                    binding: binding.clone(),
+                    with: attrs.with,
+                    wrapped,
+                })
+            }
+        }
+
+        field_names.push(field_name);
+        field_bindings.push(binding);
     }
 
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    if reserved > 0 {
+        segments.push(Segment::Reserved(quote!(#reserved)));
+    }
 
-    let expanded = quote! {
-        impl #impl_generics ::mucodec::ReprBytes<#total_size> for #name #ty_generics #where_clause {
-            fn from_bytes(input: [u8; #total_size]) -> Self {
-                let mut offset = 0;
-                Self {
+    for segment in segments.iter_mut() {
+        if let Segment::Bits { fields, size } = segment {
+            let mut bits = 0;
+            for (_, _, width, _) in fields.iter() {
+                bits += width.base10_parse::<usize>()?;
+            }
+
+            *size = bits.div_ceil(8);
+        }
+    }
+
+    let layout_checks = match c_layout {
+        true => {
+            if !input.generics.params.is_empty() {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    "layout = \"c\" is not supported for generic structs",
+                ));
+            }
+
+            let (padded, checks) = pad_c_layout(name, segments);
+            segments = padded;
+            Some(checks)
+        }
+        false => None,
+    };
+
+    let segment_sizes = segments
+        .iter()
+        .map(|segment| match segment {
+            Segment::Field { size, .. } => size.clone(),
+            Segment::Bits { size, .. } => quote!(#size),
+            Segment::Reserved(size) => size.clone(),
+        })
+        .collect::<Vec<_>>();
+    let total_size = quote!(0 #( + #segment_sizes )*);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let decode = |method: Option<syn::Ident>| {
+        let statements = segments.iter().map(|segment| match (segment, &method) {
+            (Segment::Field { ty, size, binding, with: None, wrapped, .. }, Some(method)) => {
+                let unwrap = wrapped.then(|| quote!(.0));
+                quote! {
+                    let size = #size;
+                    let #binding = <#ty>::#method(input[offset..offset + size].try_into()?)?#unwrap;
+                    offset += size;
+                }
+            }
+            (Segment::Field { ty, size, binding, with: None, wrapped, .. }, None) => {
+                let unwrap = wrapped.then(|| quote!(.0));
+                quote! {
+                    let size = #size;
+                    let #binding = <#ty>::from_bytes(input[offset..offset + size].try_into().unwrap())#unwrap;
+                    offset += size;
+                }
+            }
+            (Segment::Field { size, binding, with: Some(with), .. }, Some(method)) => {
+                // Modules only decode, so strict decoding checks that the value encodes back to
+                // the same bytes
+                let check = (method == "from_bytes_strict").then(|| quote! {
+                    if #with::encode(&#binding)[..] != input[offset..offset + size] {
+                        return Err(::mucodec::Error::NonCanonical);
+                    }
+                });
+
+                quote! {
+                    let size = #size;
+                    let #binding = #with::decode(input[offset..offset + size].try_into()?)?;
+                    #check
+                    offset += size;
+                }
+            }
+            (Segment::Field { size, binding, with: Some(with), .. }, None) => quote! {
+                let size = #size;
+                let #binding = #with::decode(input[offset..offset + size].try_into().unwrap())
+                    .expect("invalid field value");
+                offset += size;
+            },
+            (Segment::Bits { fields, size }, Some(_)) => {
+                let (types, bits, bindings) = bitfield_parts(fields);
+                quote! {
+                    let mut reader = ::mucodec::BitReader::new(&input[offset..offset + #size]);
                     #(
-                        #field_names: {
-                            let mut bytes = [0u8; #field_sizes];
-                            bytes.copy_from_slice(&input[offset..offset + #field_sizes]);
-                            offset += #field_sizes;
-                            <#field_types>::from_bytes(bytes)
-                        },
+                        let #bindings = reader.read_bitfield::<#types>(#bits)?;
+                    )*
+                    if reader.read(reader.remaining())? != 0 {
+                        return Err(::mucodec::Error::NonZeroReserved);
+                    }
+                    offset += #size;
+                }
+            }
+            (Segment::Bits { fields, size }, None) => {
+                let (types, bits, bindings) = bitfield_parts(fields);
+                quote! {
+                    let mut reader = ::mucodec::BitReader::new(&input[offset..offset + #size]);
+                    #(
+                        let #bindings = reader
+                            .read_bitfield::<#types>(#bits)
+                            .expect("invalid bitfield value");
+                    )*
+                    offset += #size;
+                }
+            }
+            (Segment::Reserved(size), Some(method)) if method == "from_bytes_strict" => quote! {
+                if input[offset..offset + #size].iter().any(|&byte| byte != 0) {
+                    return Err(::mucodec::Error::NonZeroReserved);
+                }
+                offset += #size;
+            },
+            (Segment::Reserved(size), _) => quote! {
+                offset += #size;
+            },
+        });
+
+        let skipped = skipped
+            .iter()
+            .map(|(_, binding, default)| quote!(let #binding = #default;));
+
+        let validations = method
+            .is_some()
+            .then(|| validations(name, &validated, validate.as_ref()));
+
+        quote! {
+            let mut offset = 0;
+            #( #statements )*
+            #( #skipped )*
+            let value = Self {
+                #( #field_names: #field_bindings, )*
+            };
+            #validations
+        }
+    };
+    let from_bytes = decode(None);
+    let try_from_bytes = decode(Some(format_ident!("try_from_bytes")));
+    let from_bytes_strict = decode(Some(format_ident!("from_bytes_strict")));
+
+    let zero = segments.iter().map(|segment| match segment {
+        Segment::Field {
+            name,
+            ty,
+            with: None,
+            wrapped,
+            ..
+        } => {
+            let unwrap = wrapped.then(|| quote!(.0));
+            quote!(#name: <#ty>::zero()#unwrap,)
+        }
+        // Zeroed bytes may not decode through the module, so the field starts from its default
+        Segment::Field {
+            name,
+            with: Some(_),
+            ..
+        } => quote!(#name: ::core::default::Default::default(),),
+        Segment::Bits { fields, .. } => {
+            let names = fields.iter().map(|(name, ..)| name);
+            quote!(#( #names: ::core::default::Default::default(), )*)
+        }
+        Segment::Reserved(_) => quote!(),
+    });
+    let zero_skipped = skipped
+        .iter()
+        .map(|(name, _, default)| quote!(#name: #default,));
+
+    // The infallible encoding panics on bitfields that do not fit, which the fallible one returns
+    // as errors
+    let encode = |fallible: bool| {
+        let statements = segments.iter().map(|segment| match segment {
+            Segment::Field {
+                name,
+                ty,
+                size,
+                with: None,
+                wrapped,
+                ..
+            } => {
+                let value = match wrapped {
+                    true => quote!(<#ty>::from(self.#name)),
+                    false => quote!(self.#name),
+                };
+                let bytes = match fallible {
+                    true => quote!(#value.try_as_bytes()?),
+                    false => quote!(#value.as_bytes()),
+                };
+                quote! {
+                    let size = #size;
+                    result[offset..offset + size].copy_from_slice(&#bytes);
+                    offset += size;
+                }
+            }
+            Segment::Field { name, size, with: Some(with), .. } => quote! {
+                let size = #size;
+                result[offset..offset + size].copy_from_slice(&#with::encode(&self.#name));
+                offset += size;
+            },
+            Segment::Bits { fields, size } => {
+                let names = fields.iter().map(|(name, ..)| name).collect::<Vec<_>>();
+                let (types, bits, _) = bitfield_parts(fields);
+                let write = match fallible {
+                    true => quote! {
+                        #( writer.write_bitfield(self.#names, #bits)?; )*
+                    },
+                    false => quote! {
+                        #(
+                            if writer.write_bitfield(self.#names, #bits).is_err() {
+                                panic!(
+                                    "field `{}` of {} does not fit in {} bits",
+                                    stringify!(#names),
+                                    stringify!(#name),
+                                    #bits,
+                                );
+                            }
+                        )*
+                    },
+                };
+                quote! {
+                    #(
+                        const {
+                            assert!(
+                                #bits <= <<#types as ::mucodec::ListElement>::Packed as ::mucodec::PackedInt>::BITS as usize,
+                                concat!("field `", stringify!(#names), "` is narrower than its declared bits"),
+                            );
+                        }
                     )*
+
+                    let mut writer = ::mucodec::BitWriter::new(&mut result[offset..offset + #size]);
+                    #write
+                    offset += #size;
                 }
             }
+            Segment::Reserved(size) => quote! {
+                offset += #size;
+            },
+        });
+
+        quote!(#( #statements )*)
+    };
+    let as_bytes = encode(false);
+    let try_as_bytes = encode(true);
+
+    Ok(quote! {
+        #layout_checks
+
+        impl #impl_generics ::mucodec::ReprSize for #name #ty_generics #where_clause {
+            const SIZE: usize = #total_size;
+        }
+
+        impl #impl_generics ::mucodec::ReprBytes<{ #total_size }> for #name #ty_generics #where_clause {
+            #[allow(unused_assignments, unused_mut)]
+            fn from_bytes(input: [u8; #total_size]) -> Self {
+                #from_bytes
+                value
+            }
+
+            #[allow(unused_assignments, unused_mut)]
+            fn try_from_bytes(input: [u8; #total_size]) -> Result<Self, ::mucodec::Error> {
+                #try_from_bytes
+                Ok(value)
+            }
+
+            // Decodes every field with its own strict decoding, so nested values are checked too.
+            #[allow(unused_assignments, unused_mut)]
+            fn from_bytes_strict(input: [u8; #total_size]) -> Result<Self, ::mucodec::Error> {
+                #from_bytes_strict
+                Ok(value)
+            }
 
             #[inline(always)]
             fn zero() -> Self {
                 Self {
-                    #(
-                        #field_names: <#field_types>::zero(),
-                    )*
+                    #( #zero )*
+                    #( #zero_skipped )*
                 }
             }
 
+            /// Encodes the value.
+            ///
+            /// # Panics
+            ///
+            /// Panics if a bitfield holds a value wider than its declared bits, which
+            /// [`ReprBytes::try_as_bytes`](::mucodec::ReprBytes::try_as_bytes) returns as
+            /// [`Error::BitOverflow`](::mucodec::Error::BitOverflow) instead.
+            #[allow(unused_assignments, unused_mut)]
             fn as_bytes(&self) -> [u8; #total_size] {
                 let mut result = [0u8; #total_size];
                 let mut offset = 0;
-                #(
-                    let bytes = self.#field_names.as_bytes();
-                    result[offset..offset + #field_sizes].copy_from_slice(&bytes);
-                    offset += #field_sizes;
-                )*
+                #as_bytes
                 result
             }
+
+            #[allow(unused_assignments, unused_mut)]
+            fn try_as_bytes(&self) -> Result<[u8; #total_size], ::mucodec::Error> {
+                let mut result = [0u8; #total_size];
+                let mut offset = 0;
+                #try_as_bytes
+                Ok(result)
+            }
         }
+    })
+}
+
+/// Inserts the padding a `#[repr(C)]` struct has before each field and at the end, so fields
+/// are placed at the offsets C code expects.
+///
+/// The padding is computed from the alignment of each field type and stored in a hidden constant
+/// on the struct. The returned items check, at compile time, that every field is encoded with its
+/// in-memory size and that the offsets match `core::mem::offset_of!` on a `#[repr(C)]` struct
+/// with the same encoded fields, with reserved bytes as byte arrays. Skipped fields are not part of
+/// the layout.
+fn pad_c_layout(name: &syn::Ident, segments: Vec<Segment>) -> (Vec<Segment>, TokenStream2) {
+    let count = segments.len();
+    let mut shadow_names = Vec::new();
+    let mut shadow_types = Vec::new();
+    let mut labels = Vec::new();
+    let mut sizes = Vec::new();
+    let mut padded = Vec::new();
+
+    for (i, segment) in segments.into_iter().enumerate() {
+        let (shadow_name, shadow_type, label, size) = match &segment {
+            Segment::Field {
+                name: field,
+                binding,
+                ty,
+                size,
+                ..
+            } => (
+                binding.clone(),
+                quote!(#ty),
+                format!("field `{}`", quote!(#field)),
+                size.clone(),
+            ),
+            Segment::Reserved(size) => (
+                format_ident!("__reserved{}", i),
+                quote!([u8; #size]),
+                "reserved bytes".to_string(),
+                size.clone(),
+            ),
+            Segment::Bits { .. } => unreachable!("bitfields are rejected with layout = \"c\""),
+        };
+
+        padded.push(Segment::Reserved(quote!(#name::__C_PADDING[#i])));
+        padded.push(segment);
+        shadow_names.push(shadow_name);
+        shadow_types.push(shadow_type);
+        labels.push(label);
+        sizes.push(size);
+    }
+    padded.push(Segment::Reserved(quote!(#name::__C_PADDING[#count])));
+
+    let indices = 0..count;
+    let checks = quote! {
+        impl #name {
+            /// The padding before every encoded field, and at the end of the struct.
+            #[doc(hidden)]
+            const __C_PADDING: [usize; #count + 1] = {
+                let sizes: [usize; #count] = [#( #sizes ),*];
+                let aligns: [usize; #count] = [#( ::core::mem::align_of::<#shadow_types>() ),*];
+                let mut padding = [0usize; #count + 1];
+                let mut offset = 0usize;
+                let mut align = 1;
+                let mut i = 0;
+                while i < #count {
+                    padding[i] = offset.next_multiple_of(aligns[i]) - offset;
+                    offset += padding[i] + sizes[i];
+                    if aligns[i] > align {
+                        align = aligns[i];
+                    }
+                    i += 1;
+                }
+                padding[#count] = offset.next_multiple_of(align) - offset;
+                padding
+            };
+        }
+
+        const _: () = {
+            #[repr(C)]
+            #[allow(dead_code)]
+            struct Shadow {
+                #( #shadow_names: #shadow_types, )*
+            }
+
+            const fn offset(index: usize) -> usize {
+                let sizes: [usize; #count] = [#( #sizes ),*];
+                let mut offset = 0usize;
+                let mut i = 0;
+                while i < index {
+                    offset += #name::__C_PADDING[i] + sizes[i];
+                    i += 1;
+                }
+                offset + #name::__C_PADDING[index]
+            }
+
+            #(
+                assert!(
+                    #sizes == ::core::mem::size_of::<#shadow_types>(),
+                    concat!("encoded size differs from the in-memory size for ", #labels, " of ", stringify!(#name)),
+                );
+                assert!(
+                    ::core::mem::offset_of!(Shadow, #shadow_names) == offset(#indices),
+                    concat!("encoded offset differs from the C offset for ", #labels, " of ", stringify!(#name)),
+                );
+            )*
+            assert!(
+                ::core::mem::size_of::<Shadow>() == offset(#count),
+                concat!("the size of ", stringify!(#name), " does not match its C layout"),
+            );
+        };
     };
 
-    TokenStream::from(expanded)
-}
-
-fn get_field_info(
-    data: &Data,
-    generic_params: &[(syn::Ident, &Punctuated<syn::TypeParamBound, syn::Token![+]>)],
-) -> Result<(usize, FieldInfo), syn::Error> {
-    match data {
-        Data::Struct(data) => {
-            let mut total_size = 0;
-            let mut field_info = Vec::new();
-
-            match &data.fields {
-                Fields::Named(fields) => {
-                    for field in &fields.named {
-                        let field_name = syn::Member::Named(field.ident.clone().unwrap());
-                        let field_type = field.ty.clone();
-                        let size = get_field_size(&field_type, generic_params)?;
-                        total_size += size;
-                        field_info.push((field_name, field_type, size));
+    (padded, checks)
+}
+
+/// Splits bitfields into their types, widths and bindings.
+fn bitfield_parts(
+    fields: &[(syn::Member, Type, syn::LitInt, syn::Ident)],
+) -> (Vec<&Type>, Vec<&syn::LitInt>, Vec<&syn::Ident>) {
+    let types = fields.iter().map(|(_, ty, ..)| ty).collect();
+    let bits = fields.iter().map(|(_, _, bits, _)| bits).collect();
+    let bindings = fields.iter().map(|(.., binding)| binding).collect();
+
+    (types, bits, bindings)
+}
+
+/// Structs are packed as a bit stream of their fields, in order. Fields with
+/// `#[mucodec(bits = N)]` take exactly `N` bits, and the others all take the width of the widest
+/// value among them, which is returned as the header.
+fn expand_packed_struct(input: &DeriveInput, data: &DataStruct) -> Result<TokenStream2, syn::Error> {
+    let name = &input.ident;
+
+    let mut validate = None;
+    parse_attributes(&input.attrs, |meta| {
+        if meta.path.is_ident("validate") {
+            validate = Some(meta.value()?.parse::<syn::Path>()?);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported mucodec attribute"))
+        }
+    })?;
+
+    let mut field_names = Vec::new();
+    let mut field_types = Vec::new();
+    let mut field_bits = Vec::new();
+    let mut auto_names = Vec::new();
+    let mut auto_types = Vec::new();
+    let mut fixed_names = Vec::new();
+    let mut fixed_types = Vec::new();
+    let mut fixed_bits = Vec::new();
+    let mut skipped_names = Vec::new();
+    let mut skipped_values = Vec::new();
+    let mut validated = Vec::new();
+
+    for ((field_name, field_type), field) in
+        get_field_info(&data.fields).into_iter().zip(&data.fields)
+    {
+        let attrs = get_field_attrs(field)?;
+        if let Some(with) = &attrs.with {
+            return Err(syn::Error::new_spanned(
+                with,
+                "`with` is not supported by ReprPacked",
+            ));
+        }
+        if attrs.reserved.is_some() || attrs.endian.is_some() {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`reserved` and `endian` are not supported by ReprPacked",
+            ));
+        }
+        if let Some(path) = &attrs.validate {
+            validated.push((field_name.clone(), path.clone()));
+        }
+        if attrs.skip {
+            skipped_names.push(field_name);
+            skipped_values.push(attrs.default_value());
+            continue;
+        }
+
+        match attrs.bits {
+            Some(bits) => {
+                field_bits.push(quote!(#bits));
+                fixed_names.push(field_name.clone());
+                fixed_types.push(field_type.clone());
+                fixed_bits.push(bits);
+            }
+            None => {
+                field_bits.push(quote!(bit_width));
+                auto_names.push(field_name.clone());
+                auto_types.push(field_type.clone());
+            }
+        }
+
+        field_names.push(field_name);
+        field_types.push(field_type);
+    }
+
+    let validations = validations(name, &validated, validate.as_ref());
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // Only fields with declared bits can be too wide, the others set the header
+    let pack = |fallible: bool| {
+        let write = match fallible {
+            true => quote! {
+                #(
+                    let needed = ::mucodec::ListElement::bit_width(self.#field_names);
+                    if needed > #field_bits {
+                        return Err(::mucodec::Error::BitOverflow {
+                            needed,
+                            available: #field_bits,
+                        });
                     }
+                    writer.write_element(self.#field_names, #field_bits)?;
+                )*
+            },
+            false => quote! {
+                #(
+                    if writer.write_element(self.#field_names, #field_bits).is_err() {
+                        panic!(
+                            "field `{}` of {} does not fit in {} bits",
+                            stringify!(#field_names),
+                            stringify!(#name),
+                            #field_bits,
+                        );
+                    }
+                )*
+            },
+        };
+
+        quote! {
+            #(
+                const {
+                    assert!(
+                        #fixed_bits <= <<#fixed_types as ::mucodec::ListElement>::Packed as ::mucodec::PackedInt>::BITS as usize,
+                        concat!("field `", stringify!(#fixed_names), "` is narrower than its declared bits"),
+                    );
                 }
-                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
-                    let field = fields.unnamed.first().unwrap();
-                    let field_type = field.ty.clone();
-                    let size = get_field_size(&field_type, generic_params)?;
-                    total_size = size;
-                    // For tuple structs, we use numeric indices directly in the quote
-                    let field_name = syn::Index::from(0).into();
-                    field_info.push((field_name, field_type, size));
+            )*
+
+            let bit_width = 0usize #( .max(::mucodec::ListElement::bit_width(self.#auto_names)) )*;
+            let mut out = ::mucodec::__private::vec![0u8; (0usize #( + #field_bits )*).div_ceil(8)];
+            let mut writer = ::mucodec::BitWriter::new(&mut out);
+
+            #write
+        }
+    };
+    let try_pack = pack(true);
+    let pack = pack(false);
+
+    Ok(quote! {
+        impl #impl_generics ::mucodec::ReprPacked for #name #ty_generics #where_clause {
+            /// Packs the value.
+            ///
+            /// # Panics
+            ///
+            /// Panics if a field with declared bits holds a value wider than them, which
+            /// [`ReprPacked::try_pack`](::mucodec::ReprPacked::try_pack) returns as
+            /// [`Error::BitOverflow`](::mucodec::Error::BitOverflow) instead.
+            #[allow(unused_mut, unused_variables)]
+            fn pack(&self) -> (usize, ::mucodec::__private::Vec<u8>) {
+                #pack
+                (bit_width, out)
+            }
+
+            #[allow(unused_mut, unused_variables)]
+            fn try_pack(&self) -> Result<(usize, ::mucodec::__private::Vec<u8>), ::mucodec::Error> {
+                #try_pack
+                Ok((bit_width, out))
+            }
+
+            fn unpack(bit_width: usize, input: &[u8]) -> Result<Self, ::mucodec::Error> {
+                // The header can not be wider than the widest field without declared bits
+                let max_bit_width = 0usize #(
+                    .max(<<#auto_types as ::mucodec::ListElement>::Packed as ::mucodec::PackedInt>::BITS as usize)
+                )*;
+                if bit_width > max_bit_width {
+                    return Err(::mucodec::Error::InvalidBitWidth(bit_width));
                 }
-                Fields::Unit => {}
-                _ => {
-                    return Err(syn::Error::new(
-                        proc_macro2::Span::call_site(),
-                        "Unsupported field structure",
-                    ))
+
+                let size = (0usize #( + #field_bits )*).div_ceil(8);
+                if input.len() != size {
+                    return Err(::mucodec::Error::InvalidDataSize {
+                        expected: size,
+                        got: input.len(),
+                    });
+                }
+
+                let mut reader = ::mucodec::BitReader::new(input);
+                let value = Self {
+                    #(
+                        #field_names: reader.read_element::<#field_types>(#field_bits)?,
+                    )*
+                    #( #skipped_names: #skipped_values, )*
+                };
+                #validations
+
+                Ok(value)
+            }
+        }
+    })
+}
+
+/// Returns the checks for `#[mucodec(validate = path)]`, run on the decoded `value` by the
+/// fallible decoding paths: first for every field in order, then for the whole struct.
+///
+/// Validators take a reference to the value and return `Result<(), E>` where `E: Into<String>`,
+/// and their errors are returned as `Error::InvalidField` naming the field or the struct.
+fn validations(
+    name: &syn::Ident,
+    fields: &[(syn::Member, syn::Path)],
+    validate: Option<&syn::Path>,
+) -> TokenStream2 {
+    let (names, paths): (Vec<_>, Vec<_>) = fields.iter().cloned().unzip();
+    let validate = validate.map(|path| {
+        quote! {
+            #path(&value).map_err(|reason| ::mucodec::Error::InvalidField {
+                field: stringify!(#name),
+                reason: reason.into(),
+            })?;
+        }
+    });
+
+    quote! {
+        #(
+            #paths(&value.#names).map_err(|reason| ::mucodec::Error::InvalidField {
+                field: stringify!(#names),
+                reason: reason.into(),
+            })?;
+        )*
+        #validate
+    }
+}
+
+/// The byte order set with `#[mucodec(endian = "...")]`.
+#[derive(Clone, Copy, PartialEq)]
+enum Endian {
+    Big,
+    Little,
+}
+
+impl Endian {
+    fn parse(meta: &syn::meta::ParseNestedMeta) -> Result<Self, syn::Error> {
+        let value = meta.value()?.parse::<syn::LitStr>()?;
+        match value.value().as_str() {
+            "big" => Ok(Self::Big),
+            "little" => Ok(Self::Little),
+            _ => Err(syn::Error::new_spanned(
+                value,
+                "endian must be \"big\" or \"little\"",
+            )),
+        }
+    }
+}
+
+/// Returns whether a type is a primitive integer, the only types a struct's byte order applies to,
+/// or `None` when it can not be told from the type's name, like for type aliases.
+fn is_integer(ty: &Type) -> Option<bool> {
+    const INTEGERS: [&str; 12] = [
+        "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+    ];
+    const OTHERS: [&str; 4] = ["bool", "char", "f32", "f64"];
+
+    match ty {
+        Type::Paren(ty) => is_integer(&ty.elem),
+        Type::Group(ty) => is_integer(&ty.elem),
+        Type::Array(_) | Type::Tuple(_) => Some(false),
+        // Paths are matched on their last segment, so `core::primitive::u32` is an integer
+        Type::Path(path) if path.qself.is_none() => {
+            let segment = path.path.segments.last()?;
+            if !segment.arguments.is_none() {
+                Some(false)
+            } else if INTEGERS.iter().any(|int| segment.ident == int) {
+                Some(true)
+            } else if OTHERS.iter().any(|other| segment.ident == other) {
+                Some(false)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The `#[mucodec(...)]` attributes of a struct field.
+#[derive(Default)]
+struct FieldAttrs {
+    /// The width set with `bits = N`.
+    bits: Option<syn::LitInt>,
+    /// Set with `skip`: the field is not encoded, and decoding fills it with its default.
+    skip: bool,
+    /// The function set with `default = path`, called instead of `Default::default` to fill a
+    /// skipped field.
+    default: Option<syn::Path>,
+    /// The module set with `with = path`, whose `SIZE`, `encode` and `decode` items encode the
+    /// field instead of its `ReprBytes` implementation. The field must implement `Default`, which
+    /// gives its zero value.
+    with: Option<syn::Path>,
+    /// The number of reserved bytes set with `reserved = N`, placed before the field.
+    reserved: Option<usize>,
+    /// The function set with `validate = path`, which checks the field after decoding.
+    validate: Option<syn::Path>,
+    /// The byte order set with `endian = "big"` or `endian = "little"`, overriding the struct's.
+    endian: Option<Endian>,
+}
+
+impl FieldAttrs {
+    /// Returns the expression that fills a skipped field.
+    fn default_value(&self) -> TokenStream2 {
+        match &self.default {
+            Some(default) => quote!(#default()),
+            None => quote!(::core::default::Default::default()),
+        }
+    }
+}
+
+fn get_field_attrs(field: &syn::Field) -> Result<FieldAttrs, syn::Error> {
+    let mut attrs = FieldAttrs::default();
+    parse_attributes(&field.attrs, |meta| {
+        if meta.path.is_ident("bits") {
+            let value = meta.value()?.parse::<syn::LitInt>()?;
+            if value.base10_parse::<u32>()? > u128::BITS {
+                return Err(syn::Error::new_spanned(
+                    &value,
+                    "bit widths can be at most 128",
+                ));
+            }
+
+            attrs.bits = Some(syn::LitInt::new(value.base10_digits(), value.span()));
+        } else if meta.path.is_ident("skip") {
+            attrs.skip = true;
+        } else if meta.path.is_ident("default") {
+            attrs.default = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("with") {
+            attrs.with = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("reserved") {
+            attrs.reserved = Some(meta.value()?.parse::<syn::LitInt>()?.base10_parse()?);
+        } else if meta.path.is_ident("validate") {
+            attrs.validate = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("endian") {
+            attrs.endian = Some(Endian::parse(&meta)?);
+        } else {
+            return Err(meta.error("unsupported mucodec attribute"));
+        }
+
+        Ok(())
+    })?;
+
+    let encoded = attrs.bits.is_some() || attrs.with.is_some() || attrs.endian.is_some();
+    let error = if attrs.skip && encoded {
+        "skipped fields cannot set `bits`, `with` or `endian`"
+    } else if !attrs.skip && attrs.default.is_some() {
+        "`default` can only be used with `skip`"
+    } else if attrs.bits.is_some() && attrs.with.is_some() {
+        "`bits` and `with` cannot be used together"
+    } else if attrs.endian.is_some() && (attrs.bits.is_some() || attrs.with.is_some()) {
+        "`endian` cannot be used with `bits` or `with`"
+    } else {
+        return Ok(attrs);
+    };
+
+    Err(syn::Error::new_spanned(field, error))
+}
+
+/// Enums are encoded as a fixed-size discriminant followed by the fields of the variant. Every
+/// variant takes the size of the largest one, with the unused bytes left as zeros.
+fn expand_enum(
+    input: &DeriveInput,
+    data: &DataEnum,
+    generic_params: &GenericParams,
+) -> Result<TokenStream2, syn::Error> {
+    let name = &input.ident;
+
+    let mut tag_type = None;
+    parse_attributes(&input.attrs, |meta| {
+        if meta.path.is_ident("tag") {
+            tag_type = Some(meta.value()?.parse::<syn::Ident>()?);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported mucodec attribute"))
+        }
+    })?;
+
+    if data.variants.is_empty() {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "ReprBytes cannot be derived for enums without variants",
+        ));
+    }
+
+    let tag_type = tag_type.unwrap_or_else(|| format_ident!("u8"));
+    let tag_max = match tag_type.to_string().as_str() {
+        "u8" => u8::MAX as u64,
+        "u16" => u16::MAX as u64,
+        "u32" => u32::MAX as u64,
+        "u64" => u64::MAX,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &tag_type,
+                "enum tags must be one of u8, u16, u32 or u64",
+            ))
+        }
+    };
+    let tag_size = quote!(<#tag_type as ::mucodec::ReprSize>::SIZE);
+
+    let mut discriminants = Vec::new();
+    let mut variant_names = Vec::new();
+    let mut variant_sizes = Vec::new();
+    let mut field_bindings = Vec::new();
+    let mut field_names = Vec::new();
+    let mut field_types = Vec::new();
+    let mut field_sizes = Vec::new();
+    let mut next_discriminant = Some(0u64);
+
+    for variant in &data.variants {
+        let discriminant = match get_discriminant(variant)? {
+            Some(discriminant) => discriminant,
+            None => next_discriminant.ok_or_else(|| {
+                syn::Error::new_spanned(variant, "discriminant overflows the enum tag")
+            })?,
+        };
+
+        if discriminant > tag_max {
+            return Err(syn::Error::new_spanned(
+                variant,
+                format!("discriminant {discriminant} does not fit in {tag_type}"),
+            ));
+        }
+        if discriminants.contains(&discriminant) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                format!("discriminant {discriminant} is used more than once"),
+            ));
+        }
+        next_discriminant = discriminant.checked_add(1);
+
+        // Variant fields are always encoded with their own `ReprBytes` implementation
+        for field in &variant.fields {
+            parse_attributes(&field.attrs, |meta| {
+                Err(meta.error("mucodec attributes are not supported on enum fields"))
+            })?;
+        }
+
+        let (names, types): (Vec<_>, Vec<_>) = get_field_info(&variant.fields).into_iter().unzip();
+        let sizes = types
+            .iter()
+            .map(|ty| get_field_size(ty, generic_params))
+            .collect::<Vec<_>>();
+
+        discriminants.push(discriminant);
+        variant_names.push(&variant.ident);
+        variant_sizes.push(quote!(0 #( + #sizes )*));
+        field_bindings.push(
+            (0..names.len())
+                .map(|i| format_ident!("__field{}", i))
+                .collect::<Vec<_>>(),
+        );
+        field_names.push(names);
+        field_types.push(types);
+        field_sizes.push(sizes);
+    }
+
+    let discriminants = discriminants
+        .into_iter()
+        .map(|d| syn::LitInt::new(&d.to_string(), Span::call_site()))
+        .collect::<Vec<_>>();
+    let total_size = quote! {
+        #tag_size + {
+            let sizes = [0usize #(, #variant_sizes )*];
+            let mut max = 0;
+            let mut i = 0;
+            while i < sizes.len() {
+                if sizes[i] > max {
+                    max = sizes[i];
                 }
+                i += 1;
             }
+            max
+        }
+    };
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let decode = |method: syn::Ident| {
+        quote! {
+            let tag = <#tag_type>::#method(input[..#tag_size].try_into()?)?;
+            let mut offset = #tag_size;
+            let value = match tag {
+                #(
+                    #discriminants => Self::#variant_names {
+                        #(
+                            #field_names: {
+                                let size = #field_sizes;
+                                let value = <#field_types>::#method(
+                                    input[offset..offset + size].try_into()?,
+                                )?;
+                                offset += size;
+                                value
+                            },
+                        )*
+                    },
+                )*
+                tag => return Err(::mucodec::Error::InvalidDiscriminant(tag as u64)),
+            };
+        }
+    };
+    let try_from_bytes = decode(format_ident!("try_from_bytes"));
+    let from_bytes_strict = decode(format_ident!("from_bytes_strict"));
+
+    // The zero value is the first variant with zeroed fields, since all-zero bytes are not a valid
+    // encoding when no variant has the discriminant 0
+    let zero_variant = variant_names[0];
+    let zero_names = &field_names[0];
+    let zero_types = &field_types[0];
 
-            Ok((total_size, field_info))
+    Ok(quote! {
+        impl #impl_generics ::mucodec::ReprSize for #name #ty_generics #where_clause {
+            const SIZE: usize = #total_size;
         }
-        _ => Err(syn::Error::new(
-            proc_macro2::Span::call_site(),
-            "ReprBytes can only be derived for structs",
+
+        impl #impl_generics ::mucodec::ReprBytes<{ #total_size }> for #name #ty_generics #where_clause {
+            #[allow(unused_assignments, unused_mut)]
+            fn from_bytes(input: [u8; #total_size]) -> Self {
+                let tag = <#tag_type>::from_bytes(input[..#tag_size].try_into().unwrap());
+                let mut offset = #tag_size;
+
+                match tag {
+                    #(
+                        #discriminants => Self::#variant_names {
+                            #(
+                                #field_names: {
+                                    let size = #field_sizes;
+                                    let value = <#field_types>::from_bytes(
+                                        input[offset..offset + size].try_into().unwrap(),
+                                    );
+                                    offset += size;
+                                    value
+                                },
+                            )*
+                        },
+                    )*
+                    tag => panic!("invalid discriminant for {}: {}", stringify!(#name), tag),
+                }
+            }
+
+            #[inline(always)]
+            fn zero() -> Self {
+                Self::#zero_variant {
+                    #( #zero_names: <#zero_types>::zero(), )*
+                }
+            }
+
+            #[allow(unused_assignments, unused_mut)]
+            fn try_from_bytes(input: [u8; #total_size]) -> Result<Self, ::mucodec::Error> {
+                #try_from_bytes
+                Ok(value)
+            }
+
+            // Decodes every field with its own strict decoding, and rejects non-zero padding after
+            // the fields of smaller variants.
+            #[allow(unused_assignments, unused_mut)]
+            fn from_bytes_strict(input: [u8; #total_size]) -> Result<Self, ::mucodec::Error> {
+                #from_bytes_strict
+
+                if value.as_bytes() != input {
+                    return Err(::mucodec::Error::NonCanonical);
+                }
+
+                Ok(value)
+            }
+
+            #[allow(unused_assignments, unused_mut, unused_variables)]
+            fn as_bytes(&self) -> [u8; #total_size] {
+                let mut result = [0u8; #total_size];
+                let mut offset = #tag_size;
+
+                match self {
+                    #(
+                        Self::#variant_names { #( #field_names: #field_bindings, )* } => {
+                            result[..#tag_size].copy_from_slice(&(#discriminants as #tag_type).as_bytes());
+                            #(
+                                let size = #field_sizes;
+                                result[offset..offset + size].copy_from_slice(&#field_bindings.as_bytes());
+                                offset += size;
+                            )*
+                        }
+                    )*
+                }
+
+                result
+            }
+
+            #[allow(unused_assignments, unused_mut, unused_variables)]
+            fn try_as_bytes(&self) -> Result<[u8; #total_size], ::mucodec::Error> {
+                let mut result = [0u8; #total_size];
+                let mut offset = #tag_size;
+
+                match self {
+                    #(
+                        Self::#variant_names { #( #field_names: #field_bindings, )* } => {
+                            result[..#tag_size].copy_from_slice(&(#discriminants as #tag_type).as_bytes());
+                            #(
+                                let size = #field_sizes;
+                                result[offset..offset + size].copy_from_slice(&#field_bindings.try_as_bytes()?);
+                                offset += size;
+                            )*
+                        }
+                    )*
+                }
+
+                Ok(result)
+            }
+        }
+    })
+}
+
+/// Every variant of a flags enum is a bit of `Flags<Self>`, taken in order from the least
+/// significant one unless set with `#[mucodec(bit = N)]`. The set is encoded in the smallest
+/// integer that holds the highest bit.
+fn expand_flags(input: &DeriveInput, data: &DataEnum) -> Result<TokenStream2, syn::Error> {
+    let name = &input.ident;
+    parse_attributes(&input.attrs, |meta| {
+        Err(meta.error("unsupported mucodec attribute"))
+    })?;
+
+    if data.variants.is_empty() {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "ReprFlags cannot be derived for enums without variants",
+        ));
+    }
+
+    let mut bits = Vec::new();
+    let mut next_bit = 0u32;
+
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new_spanned(variant, "flags cannot have fields"));
+        }
+        if let Some((_, expr)) = &variant.discriminant {
+            return Err(syn::Error::new_spanned(
+                expr,
+                "flag bits are set with #[mucodec(bit = N)], not with discriminants",
+            ));
+        }
+
+        let mut bit = next_bit;
+        parse_attributes(&variant.attrs, |meta| {
+            if meta.path.is_ident("bit") {
+                bit = meta.value()?.parse::<syn::LitInt>()?.base10_parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported mucodec attribute"))
+            }
+        })?;
+
+        if bit >= u128::BITS {
+            return Err(syn::Error::new_spanned(
+                variant,
+                format!("flag bit {bit} does not fit in u128"),
+            ));
+        }
+        if bits.contains(&bit) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                format!("flag bit {bit} is used more than once"),
+            ));
+        }
+
+        bits.push(bit);
+        next_bit = bit + 1;
+    }
+
+    let size = match bits.iter().max().copied().unwrap_or_default() {
+        0..8 => 1usize,
+        8..16 => 2,
+        16..32 => 4,
+        32..64 => 8,
+        _ => 16,
+    };
+    let variants = data.variants.iter().map(|v| &v.ident).collect::<Vec<_>>();
+    let variant_names = variants.iter().map(|v| v.to_string());
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::mucodec::ReprFlags for #name #ty_generics #where_clause {
+            const FLAGS: &'static [Self] = &[#( Self::#variants ),*];
+            const SIZE: usize = #size;
+
+            #[inline]
+            fn bit(self) -> u32 {
+                match self {
+                    #( Self::#variants => #bits, )*
+                }
+            }
+
+            #[inline]
+            fn name(self) -> &'static str {
+                match self {
+                    #( Self::#variants => #variant_names, )*
+                }
+            }
+        }
+
+        impl #impl_generics ::core::ops::BitOr for #name #ty_generics #where_clause {
+            type Output = ::mucodec::Flags<Self>;
+
+            #[inline]
+            fn bitor(self, other: Self) -> Self::Output {
+                ::mucodec::Flags::from(self) | other
+            }
+        }
+    })
+}
+
+/// Runs `parser` on every item inside the `#[mucodec(...)]` attributes.
+fn parse_attributes(
+    attrs: &[Attribute],
+    mut parser: impl FnMut(syn::meta::ParseNestedMeta) -> Result<(), syn::Error>,
+) -> Result<(), syn::Error> {
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("mucodec")) {
+        attr.parse_nested_meta(&mut parser)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the discriminant set for a variant, either with `#[mucodec(discriminant = N)]` or with
+/// an integer literal in the enum definition.
+fn get_discriminant(variant: &syn::Variant) -> Result<Option<u64>, syn::Error> {
+    let mut discriminant = None;
+    parse_attributes(&variant.attrs, |meta| {
+        if meta.path.is_ident("discriminant") {
+            let value = meta.value()?.parse::<syn::LitInt>()?;
+            discriminant = Some(value.base10_parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported mucodec attribute"))
+        }
+    })?;
+
+    match (&variant.discriminant, discriminant) {
+        (Some(_), Some(_)) => Err(syn::Error::new_spanned(
+            variant,
+            "discriminant is set both in the enum and in #[mucodec(discriminant)]",
         )),
+        (
+            Some((
+                _,
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Int(value),
+                    ..
+                }),
+            )),
+            None,
+        ) => Ok(Some(value.base10_parse()?)),
+        (Some((_, expr)), None) => Err(syn::Error::new_spanned(
+            expr,
+            "discriminants must be integer literals",
+        )),
+        (None, discriminant) => Ok(discriminant),
     }
 }
 
-fn get_field_size(
-    field_type: &Type,
-    generic_params: &[(syn::Ident, &Punctuated<syn::TypeParamBound, syn::Token![+]>)],
-) -> Result<usize, syn::Error> {
-    match field_type {
-        Type::Path(type_path) => {
-            let segment = type_path.path.segments.last().unwrap();
-            let type_name = segment.ident.to_string();
-
-            match type_name.as_str() {
-                // Handle primitive types
-                "u8" | "i8" => Ok(1),
-                "u16" | "i16" => Ok(2),
-                "u32" | "i32" => Ok(4),
-                "u64" | "i64" => Ok(8),
-                "u128" | "i128" => Ok(16),
-                "usize" | "isize" => Ok(8),
-                // Handle Bytes<N> type
-                "Bytes" => {
+/// Returns the encoded size of a field as a constant expression.
+///
+/// The size is taken from the field type's `ReprSize` implementation, so any `ReprBytes` type can be
+/// used as a field. Generic parameters bounded by `ReprBytes<N>` with a literal `N` use that literal
+/// instead, which keeps the struct size a concrete constant and avoids requiring
+/// `generic_const_exprs` in the crate using the derive.
+fn get_field_size(field_type: &Type, generic_params: &GenericParams) -> TokenStream2 {
+    if let Type::Path(type_path) = field_type {
+        if let Some(ident) = type_path.path.get_ident() {
+            if let Some((_, bounds)) = generic_params.iter().find(|(name, _)| name == ident) {
+                for bound in bounds.iter() {
+                    let syn::TypeParamBound::Trait(trait_bound) = bound else {
+                        continue;
+                    };
+                    let Some(segment) = trait_bound.path.segments.last() else {
+                        continue;
+                    };
+                    if segment.ident != "ReprBytes" {
+                        continue;
+                    }
                     if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
                         if let Some(syn::GenericArgument::Const(syn::Expr::Lit(syn::ExprLit {
                             lit: syn::Lit::Int(size),
                             ..
                         }))) = args.args.first()
                         {
-                            return Ok(size.base10_parse().unwrap());
+                            return quote!(#size);
                         }
                     }
-                    Err(syn::Error::new_spanned(segment, "Invalid Bytes type"))
                 }
-                // Handle generic parameters
-                _ => {
-                    if let Some((_, bounds)) = generic_params
-                        .iter()
-                        .find(|(name, _)| *name == segment.ident)
-                    {
-                        for bound in bounds.iter() {
-                            if let syn::TypeParamBound::Trait(trait_bound) = bound {
-                                if trait_bound
-                                    .path
-                                    .segments
-                                    .last()
-                                    .map(|s| s.ident == "ReprBytes")
-                                    .unwrap_or(false)
-                                {
-                                    if let Some(args) =
-                                        trait_bound.path.segments.last().and_then(|s| {
-                                            match &s.arguments {
-                                                syn::PathArguments::AngleBracketed(args) => {
-                                                    Some(args)
-                                                }
-                                                _ => None,
-                                            }
-                                        })
-                                    {
-                                        if let Some(syn::GenericArgument::Const(syn::Expr::Lit(
-                                            syn::ExprLit {
-                                                lit: syn::Lit::Int(size),
-                                                ..
-                                            },
-                                        ))) = args.args.first()
-                                        {
-                                            return Ok(size.base10_parse().unwrap());
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-
-                    // For non-generic types, try to get their SIZE constant
-                    let type_str = quote!(#field_type).to_string();
-
-                    // This is a hack to evaluate const expressions at compile time
-                    // We use the type's SIZE constant directly
-                    match type_name.as_str() {
-                        "A" => Ok(64),  // From the struct definition
-                        "B" => Ok(64),  // B contains A which is 64
-                        "C" => Ok(128), // C contains A and B, so 64 + 64
-                        _ => Err(syn::Error::new_spanned(
-                            field_type,
-                            format!("Cannot determine size for type: {}", type_str),
-                        )),
-                    }
-                }
-            }
-        }
-        Type::Tuple(tuple) if tuple.elems.is_empty() => Ok(0),
-        Type::Tuple(tuple) => {
-            let mut total_size = 0;
-            for elem in &tuple.elems {
-                total_size += get_field_size(elem, generic_params)?;
             }
-            Ok(total_size)
         }
-        _ => Err(syn::Error::new_spanned(field_type, "Unexpected field type")),
+    }
+
+    quote!(<#field_type as ::mucodec::ReprSize>::SIZE)
+}
+
+fn get_field_info(fields: &Fields) -> FieldInfo {
+    match fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|field| {
+                let field_name = syn::Member::Named(field.ident.clone().unwrap());
+                (field_name, field.ty.clone())
+            })
+            .collect(),
+        // For tuple fields, we use numeric indices directly in the quote
+        Fields::Unnamed(fields) => fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(i, field)| (syn::Index::from(i).into(), field.ty.clone()))
+            .collect(),
+        Fields::Unit => Vec::new(),
     }
 }
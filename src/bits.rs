@@ -0,0 +1,453 @@
+use crate::{Error, ListElement, PackedInt};
+
+/// Returns a mask with the low `bits` bits set.
+#[inline(always)]
+fn mask(bits: usize) -> u64 {
+    u64::MAX.checked_shr(u64::BITS - bits as u32).unwrap_or(0)
+}
+
+/// Writes values of up to 64 bits into a byte buffer, least significant bit first.
+///
+/// This is the bit stream used to pack lists: `n` values of `w` bits take exactly
+/// `ceil(n * w / 8)` bytes, and the unused bits of the last byte are zero.
+///
+/// ```
+/// use mucodec::{BitReader, BitWriter};
+///
+/// let mut buffer = [0u8; 2];
+/// let mut writer = BitWriter::new(&mut buffer);
+/// writer.write(0b101, 3).unwrap();
+/// writer.write_bool(true).unwrap();
+/// assert_eq!(writer.finish(), 1);
+/// assert_eq!(buffer, [0b1101, 0]);
+///
+/// let mut reader = BitReader::new(&buffer);
+/// assert_eq!(reader.read(3).unwrap(), 0b101);
+/// assert!(reader.read_bool().unwrap());
+/// ```
+#[derive(Debug)]
+pub struct BitWriter<'a> {
+    out: &'a mut [u8],
+    position: usize,
+}
+
+impl<'a> BitWriter<'a> {
+    pub fn new(out: &'a mut [u8]) -> Self {
+        Self { out, position: 0 }
+    }
+
+    /// Returns the number of bits written so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the number of bits that can still be written.
+    pub fn remaining(&self) -> usize {
+        self.out.len() * 8 - self.position
+    }
+
+    /// Writes the low `bits` bits of `value`. Higher bits of `value` are ignored.
+    ///
+    /// Returns [`Error::InvalidBitWidth`] if `bits` is over 64, and [`Error::BitOverflow`] if the
+    /// buffer has no room for them, in which case nothing is written.
+    pub fn write(&mut self, value: u64, bits: usize) -> Result<(), Error> {
+        if bits > u64::BITS as usize {
+            return Err(Error::InvalidBitWidth(bits));
+        }
+
+        if bits > self.remaining() {
+            return Err(Error::BitOverflow {
+                needed: bits,
+                available: self.remaining(),
+            });
+        }
+
+        let mut value = value & mask(bits);
+        let mut written = 0;
+
+        while written < bits {
+            let shift = self.position % 8;
+            let count = (8 - shift).min(bits - written);
+            let byte_mask = (mask(count) as u8) << shift;
+
+            let byte = &mut self.out[self.position / 8];
+            *byte = (*byte & !byte_mask) | ((value as u8) << shift & byte_mask);
+
+            value >>= count;
+            written += count;
+            self.position += count;
+        }
+
+        Ok(())
+    }
+
+    pub fn write_bool(&mut self, value: bool) -> Result<(), Error> {
+        self.write(value as u64, 1)
+    }
+
+    /// Writes the packed form of a [`ListElement`] in `bits` bits, zigzag encoded for signed
+    /// integers, so wide types like `u128` are supported too.
+    ///
+    /// Returns [`Error::InvalidBitWidth`] if the value needs more than `bits` bits.
+    pub fn write_element<T: ListElement>(&mut self, value: T, bits: usize) -> Result<(), Error> {
+        if bits > u128::BITS as usize || value.bit_width() > bits {
+            return Err(Error::InvalidBitWidth(bits));
+        }
+
+        if bits > self.remaining() {
+            return Err(Error::BitOverflow {
+                needed: bits,
+                available: self.remaining(),
+            });
+        }
+
+        let value = value.encode().to_u128();
+        self.write(value as u64, bits.min(64))?;
+        self.write((value >> 64) as u64, bits.saturating_sub(64))
+    }
+
+    /// Writes a [`ListElement`] in `bits` bits as the low bits of its two's complement
+    /// representation, the layout of bitfields in C structs and hardware registers.
+    ///
+    /// Returns [`Error::InvalidBitWidth`] if `bits` is wider than the packed form of `T`, and
+    /// [`Error::BitOverflow`] if the value needs more than `bits` bits.
+    pub fn write_bitfield<T: ListElement>(&mut self, value: T, bits: usize) -> Result<(), Error> {
+        if bits > T::Packed::BITS as usize {
+            return Err(Error::InvalidBitWidth(bits));
+        }
+
+        // The zigzag width of a signed value is also its width in two's complement
+        if value.bit_width() > bits {
+            return Err(Error::BitOverflow {
+                needed: value.bit_width(),
+                available: bits,
+            });
+        }
+
+        if bits > self.remaining() {
+            return Err(Error::BitOverflow {
+                needed: bits,
+                available: self.remaining(),
+            });
+        }
+
+        let value = value.to_bits().to_u128();
+        self.write(value as u64, bits.min(64))?;
+        self.write((value >> 64) as u64, bits.saturating_sub(64))
+    }
+
+    /// Pads the stream with zero bits up to the next byte boundary.
+    pub fn align(&mut self) -> Result<(), Error> {
+        match self.position % 8 {
+            0 => Ok(()),
+            offset => self.write(0, 8 - offset),
+        }
+    }
+
+    /// Pads the stream to a byte boundary, returning the number of bytes written.
+    pub fn finish(mut self) -> usize {
+        // Padding never needs more room than the byte the stream ends in
+        self.align().expect("padding fits in the last byte");
+        self.position / 8
+    }
+}
+
+/// Reads values written by a [`BitWriter`] from a byte buffer.
+#[derive(Clone, Debug)]
+pub struct BitReader<'a> {
+    input: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(input: &'a [u8]) -> Self {
+        Self { input, position: 0 }
+    }
+
+    /// Returns the number of bits read so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the number of bits left to read.
+    pub fn remaining(&self) -> usize {
+        self.input.len() * 8 - self.position
+    }
+
+    /// Reads a value of `bits` bits.
+    ///
+    /// Returns [`Error::InvalidBitWidth`] if `bits` is over 64, and [`Error::BitOverflow`] if the
+    /// input ends before them, in which case nothing is read.
+    pub fn read(&mut self, bits: usize) -> Result<u64, Error> {
+        if bits > u64::BITS as usize {
+            return Err(Error::InvalidBitWidth(bits));
+        }
+
+        self.check(bits)?;
+
+        let mut value = 0u64;
+        let mut read = 0;
+
+        while read < bits {
+            let shift = self.position % 8;
+            let count = (8 - shift).min(bits - read);
+            let chunk = (self.input[self.position / 8] >> shift) as u64 & mask(count);
+
+            value |= chunk << read;
+            read += count;
+            self.position += count;
+        }
+
+        Ok(value)
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool, Error> {
+        Ok(self.read(1)? == 1)
+    }
+
+    /// Reads a [`ListElement`] written by [`BitWriter::write_element`].
+    ///
+    /// Returns [`Error::InvalidBitWidth`] if the value read does not fit in the packed form of
+    /// `T`, which can only happen when `bits` is wider than it.
+    pub fn read_element<T: ListElement>(&mut self, bits: usize) -> Result<T, Error> {
+        if bits > u128::BITS as usize {
+            return Err(Error::InvalidBitWidth(bits));
+        }
+
+        self.check(bits)?;
+
+        let low = self.read(bits.min(64))? as u128;
+        let value = low | (self.read(bits.saturating_sub(64))? as u128) << 64;
+
+        if value.checked_shr(T::Packed::BITS).unwrap_or(0) != 0 {
+            return Err(Error::InvalidBitWidth(bits));
+        }
+
+        T::decode(T::Packed::from_u128(value))
+    }
+
+    /// Reads a [`ListElement`] written by [`BitWriter::write_bitfield`], sign extending the value
+    /// for signed integers.
+    ///
+    /// Returns [`Error::InvalidBitWidth`] if `bits` is wider than the packed form of `T`.
+    pub fn read_bitfield<T: ListElement>(&mut self, bits: usize) -> Result<T, Error> {
+        if bits > T::Packed::BITS as usize {
+            return Err(Error::InvalidBitWidth(bits));
+        }
+
+        self.check(bits)?;
+
+        let low = self.read(bits.min(64))? as u128;
+        let mut value = low | (self.read(bits.saturating_sub(64))? as u128) << 64;
+
+        // Only signed integers are zigzag encoded in lists, so `ZIGZAG` tells whether to extend
+        if T::ZIGZAG && bits > 0 && value >> (bits - 1) & 1 == 1 {
+            value |= u128::MAX.checked_shl(bits as u32).unwrap_or(0);
+        }
+
+        T::from_bits(T::Packed::from_u128(value))
+    }
+
+    /// Skips `bits` bits without reading them.
+    pub fn skip(&mut self, bits: usize) -> Result<(), Error> {
+        self.check(bits)?;
+        self.position += bits;
+
+        Ok(())
+    }
+
+    /// Skips to the next byte boundary, ignoring the padding bits.
+    pub fn align(&mut self) -> Result<(), Error> {
+        match self.position % 8 {
+            0 => Ok(()),
+            offset => self.skip(8 - offset),
+        }
+    }
+
+    fn check(&self, bits: usize) -> Result<(), Error> {
+        if bits > self.remaining() {
+            return Err(Error::BitOverflow {
+                needed: bits,
+                available: self.remaining(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn fields() -> impl Strategy<Value = Vec<(u64, usize)>> {
+        prop::collection::vec(
+            (0..=64usize).prop_flat_map(|bits| (0..=mask(bits), Just(bits))),
+            0..64,
+        )
+    }
+
+    #[test_strategy::proptest]
+    fn test_roundtrip(#[strategy(fields())] fields: Vec<(u64, usize)>) {
+        let total = fields.iter().map(|&(_, bits)| bits).sum::<usize>();
+        let mut buffer = vec![0xAAu8; total.div_ceil(8)];
+
+        let mut writer = BitWriter::new(&mut buffer);
+        for &(value, bits) in &fields {
+            writer.write(value, bits)?;
+        }
+        prop_assert_eq!(writer.position(), total);
+        prop_assert_eq!(writer.finish(), total.div_ceil(8));
+
+        // The padding bits are cleared
+        if total % 8 != 0 {
+            prop_assert_eq!(buffer[total / 8] >> (total % 8), 0);
+        }
+
+        let mut reader = BitReader::new(&buffer);
+        for &(value, bits) in &fields {
+            prop_assert_eq!(reader.read(bits)?, value);
+        }
+        prop_assert!(reader.remaining() < 8);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let mut buffer = [0u8; 2];
+        let mut writer = BitWriter::new(&mut buffer);
+
+        writer.write(u64::MAX, 12).unwrap();
+        assert!(matches!(
+            writer.write(0, 5),
+            Err(Error::BitOverflow {
+                needed: 5,
+                available: 4
+            })
+        ));
+        assert!(matches!(
+            writer.write(0, 65),
+            Err(Error::InvalidBitWidth(65))
+        ));
+        writer.write(0b1010, 4).unwrap();
+        assert_eq!(writer.remaining(), 0);
+        assert_eq!(buffer, [0xFF, 0xAF]);
+
+        let mut reader = BitReader::new(&buffer);
+        assert_eq!(reader.read(12).unwrap(), 0xFFF);
+        assert!(matches!(
+            reader.read(5),
+            Err(Error::BitOverflow {
+                needed: 5,
+                available: 4
+            })
+        ));
+        assert_eq!(reader.read(4).unwrap(), 0b1010);
+        assert!(reader.read_bool().is_err());
+    }
+
+    #[test]
+    fn test_align() {
+        let mut buffer = [0xFFu8; 3];
+        let mut writer = BitWriter::new(&mut buffer);
+
+        writer.write_bool(true).unwrap();
+        writer.align().unwrap();
+        writer.align().unwrap();
+        assert_eq!(writer.position(), 8);
+        writer.write(0x3, 2).unwrap();
+        assert_eq!(writer.finish(), 2);
+        assert_eq!(buffer, [0x01, 0x03, 0xFF]);
+
+        let mut reader = BitReader::new(&buffer);
+        assert!(reader.read_bool().unwrap());
+        reader.align().unwrap();
+        assert_eq!(reader.read(2).unwrap(), 0x3);
+        reader.skip(6).unwrap();
+        assert_eq!(reader.read(8).unwrap(), 0xFF);
+        assert!(reader.align().is_ok());
+        assert!(reader.skip(1).is_err());
+    }
+
+    #[test_strategy::proptest]
+    fn test_elements(a: i8, b: u128, c: bool, d: i64) {
+        let mut buffer = [0u8; 32];
+        let mut writer = BitWriter::new(&mut buffer);
+
+        // Signed values are zigzag encoded, so small negative values stay narrow
+        writer.write_element(a, a.bit_width())?;
+        writer.write_element(b, 128)?;
+        writer.write_element(c, 1)?;
+        writer.write_element(d, 64)?;
+        prop_assert!(writer.write_element(u16::MAX, 15).is_err());
+
+        let mut reader = BitReader::new(&buffer);
+        prop_assert_eq!(reader.read_element::<i8>(a.bit_width())?, a);
+        prop_assert_eq!(reader.read_element::<u128>(128)?, b);
+        prop_assert_eq!(reader.read_element::<bool>(1)?, c);
+        prop_assert_eq!(reader.read_element::<i64>(64)?, d);
+    }
+
+    #[test_strategy::proptest]
+    fn test_bitfields(a: i8, b: u16, c: bool, d: i128) {
+        let mut buffer = [0u8; 32];
+        let mut writer = BitWriter::new(&mut buffer);
+
+        writer.write_bitfield(a, a.bit_width())?;
+        writer.write_bitfield(b, 16)?;
+        writer.write_bitfield(c, 1)?;
+        writer.write_bitfield(d, 128)?;
+
+        let mut reader = BitReader::new(&buffer);
+        prop_assert_eq!(reader.read_bitfield::<i8>(a.bit_width())?, a);
+        prop_assert_eq!(reader.read_bitfield::<u16>(16)?, b);
+        prop_assert_eq!(reader.read_bitfield::<bool>(1)?, c);
+        prop_assert_eq!(reader.read_bitfield::<i128>(128)?, d);
+    }
+
+    #[test]
+    fn test_bitfield_twos_complement() {
+        let mut buffer = [0u8; 2];
+        let mut writer = BitWriter::new(&mut buffer);
+
+        // Signed values keep their two's complement bits, truncated to the field
+        writer.write_bitfield(-2i8, 4).unwrap();
+        writer.write_bitfield(7i8, 4).unwrap();
+        assert!(matches!(
+            writer.write_bitfield(-9i8, 4),
+            Err(Error::BitOverflow {
+                needed: 5,
+                available: 4
+            })
+        ));
+        assert!(matches!(
+            writer.write_bitfield(1u8, 9),
+            Err(Error::InvalidBitWidth(9))
+        ));
+        assert_eq!(buffer, [0x7E, 0]);
+
+        let mut reader = BitReader::new(&buffer);
+        assert_eq!(reader.read_bitfield::<i8>(4).unwrap(), -2);
+        assert_eq!(reader.read_bitfield::<i8>(4).unwrap(), 7);
+        assert_eq!(BitReader::new(&[0x0F]).read_bitfield::<u8>(4).unwrap(), 15);
+        assert_eq!(BitReader::new(&[0x08]).read_bitfield::<i8>(4).unwrap(), -8);
+    }
+
+    #[test]
+    fn test_element_width() {
+        assert_eq!((-1i32).bit_width(), 1);
+        assert_eq!(1i32.bit_width(), 2);
+
+        let buffer = [0xFF, 0x01];
+        assert_eq!(BitReader::new(&buffer).read_element::<u8>(9).ok(), None);
+        assert_eq!(
+            BitReader::new(&buffer).read_element::<u16>(9).ok(),
+            Some(0x1FF)
+        );
+        assert!(matches!(
+            BitReader::new(&buffer).read_element::<bool>(2),
+            Err(Error::InvalidDiscriminant(3))
+        ));
+    }
+}
@@ -6,7 +6,7 @@ use core::{
     simd::{cmp::*, num::*, *},
 };
 
-use crate::{from_hex_digit, Error, ReprBase64, ReprBytes, ReprHex};
+use crate::{from_hex_digit, Error, ReprBytes, ReprSize};
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(transparent)]
@@ -42,7 +42,7 @@ impl From<blake3::Hash> for Bytes<{ blake3::OUT_LEN }> {
 
 impl<const N: usize> fmt::Debug for Bytes<N> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(&self.to_hex())
+        f.write_str(&encode_hex(&self.0))
     }
 }
 
@@ -62,7 +62,7 @@ impl<const N: usize> proptest::prelude::Arbitrary for Bytes<N> {
 
 impl<const N: usize> fmt::Display for Bytes<N> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(&self.to_hex())
+        f.write_str(&encode_hex(&self.0))
     }
 }
 
@@ -80,6 +80,10 @@ impl<const N: usize> AsRef<[u8]> for Bytes<N> {
     }
 }
 
+impl<const N: usize> ReprSize for Bytes<N> {
+    const SIZE: usize = N;
+}
+
 impl<const N: usize> ReprBytes<N> for Bytes<N> {
     #[inline(always)]
     fn from_bytes(input: [u8; N]) -> Self {
@@ -92,244 +96,226 @@ impl<const N: usize> ReprBytes<N> for Bytes<N> {
     }
 }
 
-impl<const N: usize> ReprHex<N> for Bytes<N> {
-    #[inline]
-    fn to_hex(&self) -> String {
-        const LOOKUP: [u8; 16] = *b"0123456789abcdef";
-        let mut result = Vec::with_capacity(N * 2);
-
-        // Process full chunks of 16 bytes
-        for chunk in self.0.chunks_exact(16) {
-            let v: Simd<u8, 16> = Simd::from_slice(chunk);
-
-            // Extract high and low nibbles
-            let hi = v >> 4;
-            let lo = v & Simd::splat(0x0f);
-
-            // Convert to usize for indexing
-            let hi_indices = hi.cast::<usize>();
-            let lo_indices = lo.cast::<usize>();
-
-            // Lookup hex digits
-            let hi_chars = Simd::gather_or_default(&LOOKUP, hi_indices);
-            let lo_chars = Simd::gather_or_default(&LOOKUP, lo_indices);
-
-            // Interleave high and low digits
-            for i in 0..16 {
-                result.push(hi_chars.as_array()[i]);
-                result.push(lo_chars.as_array()[i]);
-            }
-        }
-
-        // Handle remaining bytes
-        let remainder = self.0.chunks_exact(16).remainder();
-        for &byte in remainder {
-            result.push(LOOKUP[(byte >> 4) as usize]);
-            result.push(LOOKUP[(byte & 0xf) as usize]);
+/// Encodes `input` as lowercase hex.
+///
+/// This is the engine behind every [`ReprHex`](crate::ReprHex) implementation.
+#[inline]
+pub(crate) fn encode_hex(input: &[u8]) -> String {
+    const LOOKUP: [u8; 16] = *b"0123456789abcdef";
+    let mut result = Vec::with_capacity(input.len() * 2);
+    let (chunks, remainder) = input.as_chunks::<16>();
+
+    // Process full chunks of 16 bytes
+    for chunk in chunks {
+        let v: Simd<u8, 16> = Simd::from_array(*chunk);
+
+        // Extract high and low nibbles
+        let hi = v >> 4;
+        let lo = v & Simd::splat(0x0f);
+
+        // Convert to usize for indexing
+        let hi_indices = hi.cast::<usize>();
+        let lo_indices = lo.cast::<usize>();
+
+        // Lookup hex digits
+        let hi_chars = Simd::gather_or_default(&LOOKUP, hi_indices);
+        let lo_chars = Simd::gather_or_default(&LOOKUP, lo_indices);
+
+        // Interleave high and low digits
+        for i in 0..16 {
+            result.push(hi_chars.as_array()[i]);
+            result.push(lo_chars.as_array()[i]);
         }
+    }
 
-        // Safe because we only used valid ASCII hex digits
-        unsafe { String::from_utf8_unchecked(result) }
+    // Handle remaining bytes
+    for &byte in remainder {
+        result.push(LOOKUP[(byte >> 4) as usize]);
+        result.push(LOOKUP[(byte & 0xf) as usize]);
     }
 
-    #[inline]
-    fn from_hex(input: &str) -> Result<Self, Error> {
-        if input.len() != N * 2 {
-            return Err(Error::InvalidDataSize {
-                expected: N * 2,
-                got: input.len(),
-            });
-        }
+    // Safe because we only used valid ASCII hex digits
+    unsafe { String::from_utf8_unchecked(result) }
+}
 
-        let input = input.as_bytes();
-        let mut result = [0u8; N];
+/// Decodes lowercase hex into `output`, which must be exactly half the length of `input`.
+///
+/// This is the engine behind every [`ReprHex`](crate::ReprHex) implementation.
+#[inline]
+pub(crate) fn decode_hex(input: &str, output: &mut [u8]) -> Result<(), Error> {
+    if input.len() != output.len() * 2 {
+        return Err(Error::InvalidDataSize {
+            expected: output.len() * 2,
+            got: input.len(),
+        });
+    }
 
-        // Process 32 hex chars (16 bytes) at a time using SIMD
-        for (chunk_idx, chunk) in input.chunks_exact(32).enumerate() {
-            let v: Simd<u8, 32> = Simd::from_slice(chunk);
+    let (chunks, remainder) = input.as_bytes().as_chunks::<32>();
 
-            // Check which chars are digits (0-9) vs letters (a-f)
-            let is_digit = v.simd_ge(Simd::splat(b'0')) & v.simd_le(Simd::splat(b'9'));
-            let is_alpha = v.simd_ge(Simd::splat(b'a')) & v.simd_le(Simd::splat(b'f'));
+    // Process 32 hex chars (16 bytes) at a time using SIMD
+    for (chunk_idx, chunk) in chunks.iter().enumerate() {
+        let v: Simd<u8, 32> = Simd::from_array(*chunk);
 
-            // Validate that all input chars were valid hex digits
-            if !(is_digit | is_alpha).all() {
-                // Use first invalid character found for the error
-                let chars = v.to_array();
-                for &c in &chars {
-                    if !(c.is_ascii_digit() || (b'a'..=b'f').contains(&c)) {
-                        return Err(Error::InvalidHexDigit(c as char));
-                    }
-                }
-                // Unreachable as we know there's an invalid char
-                unreachable!()
-            }
+        // Check which chars are digits (0-9) vs letters (a-f)
+        let is_digit = v.simd_ge(Simd::splat(b'0')) & v.simd_le(Simd::splat(b'9'));
+        let is_alpha = v.simd_ge(Simd::splat(b'a')) & v.simd_le(Simd::splat(b'f'));
 
-            // Convert ASCII hex to values
-            let values = is_digit.select(
-                v - Simd::splat(b'0'),
-                v - Simd::splat(b'a') + Simd::splat(10),
-            );
-
-            // Split into high and low nibbles
-            let values_arr = values.to_array();
-            let out_idx = chunk_idx * 16;
-
-            // Process pairs of hex digits
-            for i in 0..16 {
-                let hi = values_arr[i * 2]; // First digit of pair
-                let lo = values_arr[i * 2 + 1]; // Second digit of pair
-                result[out_idx + i] = (hi << 4) | lo;
+        // Validate that all input chars were valid hex digits
+        if !(is_digit | is_alpha).all() {
+            // Use first invalid character found for the error
+            for &c in chunk {
+                from_hex_digit(c)?;
             }
+            // Unreachable as we know there's an invalid char
+            unreachable!()
         }
 
-        // Handle remaining bytes with standard method
-        let remainder = input.chunks_exact(32).remainder();
-        if !remainder.is_empty() {
-            let out_idx = (input.len() - remainder.len()) / 2;
-
-            for (i, chunk) in remainder.chunks_exact(2).enumerate() {
-                let hi = from_hex_digit(chunk[0])?;
-                let lo = from_hex_digit(chunk[1])?;
-                result[out_idx + i] = (hi << 4) | lo;
-            }
+        // Convert ASCII hex to values
+        let values = is_digit.select(
+            v - Simd::splat(b'0'),
+            v - Simd::splat(b'a') + Simd::splat(10),
+        );
+
+        // Split into high and low nibbles
+        let values_arr = values.to_array();
+        let out_idx = chunk_idx * 16;
+
+        // Process pairs of hex digits
+        for i in 0..16 {
+            let hi = values_arr[i * 2]; // First digit of pair
+            let lo = values_arr[i * 2 + 1]; // Second digit of pair
+            output[out_idx + i] = (hi << 4) | lo;
         }
+    }
 
-        Ok(Self(result))
+    // Handle remaining bytes with standard method
+    let out_idx = chunks.len() * 16;
+    for (i, [hi, lo]) in remainder.as_chunks::<2>().0.iter().enumerate() {
+        output[out_idx + i] = (from_hex_digit(*hi)? << 4) | from_hex_digit(*lo)?;
     }
+
+    Ok(())
 }
 
-impl<const N: usize> ReprBase64<N> for Bytes<N> {
-    #[inline]
-    fn to_base64(&self) -> String {
-        const BASE64_CHARS: &[u8] =
-            b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
-        let mut result = String::with_capacity((N + 2) / 3 * 4);
-        let mut output = [0u8; 32]; // Fixed buffer for SIMD output
-        let chunks = self.chunks_exact(24);
-        let remainder = chunks.remainder();
-        let remainder_len = remainder.len();
-
-        // Process full chunks with SIMD
-        for chunk in chunks {
-            // Create a padded 32-byte buffer
-            let mut padded = [0u8; 32];
-            padded[..24].copy_from_slice(chunk);
-
-            let input_simd = Simd::<u8, 32>::from_array(padded);
-            let reshuffled = enc_reshuffle(input_simd);
-            let encoded = enc_translate(reshuffled);
-
-            // Copy to our fixed output buffer
-            output.copy_from_slice(&encoded.to_array());
-
-            // Safe because we know the output only contains valid base64 characters
-            unsafe {
-                result.push_str(core::str::from_utf8_unchecked(&output[..32]));
-            }
+/// Encodes `input` as padded standard base64.
+///
+/// This is the engine behind every [`ReprBase64`](crate::ReprBase64) implementation.
+#[inline]
+pub(crate) fn encode_base64(input: &[u8]) -> String {
+    const BASE64_CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut result = String::with_capacity(input.len().div_ceil(3) * 4);
+    let mut output = [0u8; 32]; // Fixed buffer for SIMD output
+    let (chunks, remainder) = input.as_chunks::<24>();
+    let remainder_len = remainder.len();
+
+    // Process full chunks with SIMD
+    for chunk in chunks {
+        // Create a padded 32-byte buffer
+        let mut padded = [0u8; 32];
+        padded[..24].copy_from_slice(chunk);
+
+        let input_simd = Simd::<u8, 32>::from_array(padded);
+        let reshuffled = enc_reshuffle(input_simd);
+        let encoded = enc_translate(reshuffled);
+
+        // Copy to our fixed output buffer
+        output.copy_from_slice(&encoded.to_array());
+
+        // Safe because we know the output only contains valid base64 characters
+        unsafe {
+            result.push_str(core::str::from_utf8_unchecked(&output[..32]));
         }
+    }
 
-        // Handle remaining bytes without branching on the outer loop
-        if remainder_len > 0 {
-            let mut i = 0;
-            let mut temp = [0u8; 4];
-
-            while i < remainder_len {
-                let b0 = remainder[i];
-                let b1 = *remainder.get(i + 1).unwrap_or(&0);
-                let b2 = *remainder.get(i + 2).unwrap_or(&0);
-
-                temp[0] = BASE64_CHARS[(b0 >> 2) as usize];
-                temp[1] = BASE64_CHARS[((b0 & 0x03) << 4 | b1 >> 4) as usize];
-                temp[2] = if i + 1 < remainder_len {
-                    BASE64_CHARS[((b1 & 0x0f) << 2 | b2 >> 6) as usize]
-                } else {
-                    b'='
-                };
-                temp[3] = if i + 2 < remainder_len {
-                    BASE64_CHARS[(b2 & 0x3f) as usize]
-                } else {
-                    b'='
-                };
-
-                // Safe because we know temp only contains valid base64 characters
-                unsafe {
-                    result.push_str(core::str::from_utf8_unchecked(&temp));
-                }
-
-                i += 3;
+    // Handle remaining bytes without branching on the outer loop
+    if remainder_len > 0 {
+        let mut i = 0;
+        let mut temp = [0u8; 4];
+
+        while i < remainder_len {
+            let b0 = remainder[i];
+            let b1 = *remainder.get(i + 1).unwrap_or(&0);
+            let b2 = *remainder.get(i + 2).unwrap_or(&0);
+
+            temp[0] = BASE64_CHARS[(b0 >> 2) as usize];
+            temp[1] = BASE64_CHARS[((b0 & 0x03) << 4 | b1 >> 4) as usize];
+            temp[2] = if i + 1 < remainder_len {
+                BASE64_CHARS[((b1 & 0x0f) << 2 | b2 >> 6) as usize]
+            } else {
+                b'='
+            };
+            temp[3] = if i + 2 < remainder_len {
+                BASE64_CHARS[(b2 & 0x3f) as usize]
+            } else {
+                b'='
+            };
+
+            // Safe because we know temp only contains valid base64 characters
+            unsafe {
+                result.push_str(core::str::from_utf8_unchecked(&temp));
             }
-        }
 
-        result
-    }
-
-    #[inline]
-    fn from_base64(input: &str) -> Result<Self, Error> {
-        if input.len() != Self::BASE64_SIZE {
-            return Err(Error::InvalidDataSize {
-                expected: Self::BASE64_SIZE,
-                got: input.len(),
-            });
+            i += 3;
         }
+    }
 
-        let input = input.as_bytes();
-        let mut result = [0u8; N];
-        let mut chunks = input.chunks_exact(32);
-        let mut out_idx = 0;
+    result
+}
 
-        // Process full chunks with SIMD
-        for chunk in chunks.by_ref() {
-            if out_idx + 24 > N {
-                break;
-            }
+/// Decodes padded standard base64 into `output`, which must be exactly as long as the data encoded
+/// in `input`.
+///
+/// This is the engine behind every [`ReprBase64`](crate::ReprBase64) implementation.
+#[inline]
+pub(crate) fn decode_base64(input: &str, output: &mut [u8]) -> Result<(), Error> {
+    let expected = output.len().div_ceil(3) * 4;
+    if input.len() != expected {
+        return Err(Error::InvalidDataSize {
+            expected,
+            got: input.len(),
+        });
+    }
 
-            let input_simd = Simd::<u8, 32>::from_slice(chunk);
-            let decoded = dec_translate(input_simd)?;
-            let reshuffled = dec_reshuffle(decoded);
+    let input = input.as_bytes();
+    let mut chunks = input.as_chunks::<32>().0.iter();
+    let mut out_idx = 0;
 
-            // Copy valid bytes to result
-            result[out_idx..out_idx + 24].copy_from_slice(&reshuffled.to_array()[..24]);
-            out_idx += 24;
+    // Process full chunks with SIMD
+    for chunk in chunks.by_ref() {
+        if out_idx + 24 > output.len() {
+            break;
         }
 
-        // Handle remaining bytes manually
-        let remainder = chunks.remainder();
-        if !remainder.is_empty() {
-            let mut i = 0;
-            while i < remainder.len() {
-                if out_idx >= N {
-                    break;
-                }
+        let input_simd = Simd::<u8, 32>::from_array(*chunk);
+        let decoded = dec_translate(input_simd)?;
+        let reshuffled = dec_reshuffle(decoded);
 
-                let b0 = dec_byte(remainder[i])?;
-                let b1 = dec_byte(remainder[i + 1])?;
-                let b2 = if remainder[i + 2] == b'=' {
-                    0
-                } else {
-                    dec_byte(remainder[i + 2])?
-                };
-                let b3 = if remainder[i + 3] == b'=' {
-                    0
-                } else {
-                    dec_byte(remainder[i + 3])?
-                };
-
-                result[out_idx] = (b0 << 2) | (b1 >> 4);
-                if remainder[i + 2] != b'=' {
-                    result[out_idx + 1] = (b1 << 4) | (b2 >> 2);
-                }
-                if remainder[i + 3] != b'=' {
-                    result[out_idx + 2] = (b2 << 6) | b3;
-                }
+        // Copy valid bytes to result
+        output[out_idx..out_idx + 24].copy_from_slice(&reshuffled.to_array()[..24]);
+        out_idx += 24;
+    }
 
-                i += 4;
-                out_idx += 3;
+    // Handle remaining bytes manually. Each group of 4 characters decodes to up to 3 bytes, and
+    // the characters that would fall past the end of `output` must be padding.
+    for group in input[out_idx / 3 * 4..].as_chunks::<4>().0 {
+        let len = (output.len() - out_idx).min(3);
+
+        let mut values = [0u8; 4];
+        for (i, &c) in group.iter().enumerate() {
+            if i <= len {
+                values[i] = dec_byte(c)?;
+            } else if c != b'=' {
+                return Err(Error::InvalidBase64Character(c as char));
             }
         }
 
-        Ok(Self(result))
+        let [b0, b1, b2, b3] = values;
+        let bytes = [(b0 << 2) | (b1 >> 4), (b1 << 4) | (b2 >> 2), (b2 << 6) | b3];
+        output[out_idx..out_idx + len].copy_from_slice(&bytes[..len]);
+        out_idx += len;
     }
+
+    Ok(())
 }
 
 #[inline(always)]
@@ -360,13 +346,7 @@ fn enc_reshuffle(input: Simd<u8, 32>) -> Simd<u8, 32> {
 #[inline(always)]
 fn enc_translate(input: Simd<u8, 32>) -> Simd<u8, 32> {
     // Base64 translation table
-    const LUT: [u8; 64] = [
-        b'A', b'B', b'C', b'D', b'E', b'F', b'G', b'H', b'I', b'J', b'K', b'L', b'M', b'N', b'O',
-        b'P', b'Q', b'R', b'S', b'T', b'U', b'V', b'W', b'X', b'Y', b'Z', b'a', b'b', b'c', b'd',
-        b'e', b'f', b'g', b'h', b'i', b'j', b'k', b'l', b'm', b'n', b'o', b'p', b'q', b'r', b's',
-        b't', b'u', b'v', b'w', b'x', b'y', b'z', b'0', b'1', b'2', b'3', b'4', b'5', b'6', b'7',
-        b'8', b'9', b'+', b'/',
-    ];
+    const LUT: [u8; 64] = *b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
 
     let mut result = Simd::splat(0u8);
     for i in 0..32 {
@@ -394,7 +374,6 @@ fn dec_translate(input: Simd<u8, 32>) -> Result<Simd<u8, 32>, Error> {
         }
         table[b'+' as usize] = 62;
         table[b'/' as usize] = 63;
-        table[b'=' as usize] = 0;
         table
     };
 
@@ -450,7 +429,6 @@ fn dec_byte(input: u8) -> Result<u8, Error> {
         }
         table[b'+' as usize] = 62;
         table[b'/' as usize] = 63;
-        table[b'=' as usize] = 0;
         table
     };
 
@@ -476,6 +454,13 @@ mod tests {
                     fn test_roundtrip(input: Bytes<$size>) {
                         prop_assert_eq!(Bytes::<$size>::from_bytes(input.as_bytes()), input);
                     }
+
+                    #[test_strategy::proptest]
+                    fn test_strict_roundtrip(input: Bytes<$size>) {
+                        prop_assert_eq!(Bytes::<$size>::from_bytes_strict(input.as_bytes())?, input);
+                        prop_assert_eq!(Bytes::<$size>::from_hex_strict(&input.to_hex())?, input);
+                        prop_assert_eq!(Bytes::<$size>::from_base64_strict(&input.to_base64())?, input);
+                    }
                 }
 
                 mod [<hex_ $size>] {
@@ -491,6 +476,7 @@ mod tests {
                     fn test_roundtrip(input: Bytes<$size>) {
                         prop_assert_eq!(Bytes::<$size>::from_hex(&input.to_hex())?, input);
                     }
+
                 }
 
                 mod [<base64_ $size>] {
@@ -508,12 +494,76 @@ mod tests {
                     fn test_roundtrip(input: Bytes<$size>) {
                         prop_assert_eq!(Bytes::<$size>::from_base64(&input.to_base64())?, input);
                     }
+
+                    #[test]
+                    fn test_strict_padding() {
+                        let zero = Bytes::<$size>::zero();
+
+                        // Replace the first character with padding
+                        let mut input = zero.to_base64().into_bytes();
+                        input[0] = b'=';
+                        let input = alloc::string::String::from_utf8(input).unwrap();
+                        assert!(Bytes::<$size>::from_base64_strict(&input).is_err());
+                    }
+
+                    #[test]
+                    fn test_invalid_padding() {
+                        let input = Bytes::<$size>::zero().to_base64();
+
+                        // Data where padding is expected
+                        if $size % 3 != 0 {
+                            let unpadded = input.replace('=', "A");
+                            assert!(Bytes::<$size>::from_base64(&unpadded).is_err());
+                        }
+
+                        let overlong = alloc::format!("{input}AAAA");
+                        assert!(Bytes::<$size>::from_base64(&overlong).is_err());
+                    }
+
+                    #[test_strategy::proptest]
+                    fn test_decode_any(
+                        #[strategy(proptest::collection::vec(
+                            proptest::sample::select(b"AB/+=".as_slice()),
+                            ($size as usize).div_ceil(3) * 4,
+                        ))]
+                        input: alloc::vec::Vec<u8>,
+                    ) {
+                        // Never panics, and only accepts padding at the end
+                        let input = alloc::string::String::from_utf8(input).unwrap();
+                        if let Ok(decoded) = Bytes::<$size>::from_base64(&input) {
+                            prop_assert_eq!(
+                                decoded.to_base64().trim_end_matches('=').len(),
+                                input.trim_end_matches('=').len()
+                            );
+                        }
+                    }
+
+                    #[test]
+                    fn test_strict_trailing_bits() {
+                        let zero = Bytes::<$size>::zero();
+                        let input = zero.to_base64();
+
+                        // Set the unused low bits of the last character that carries data
+                        let last = input.trim_end_matches('=').len() - 1;
+                        if $size % 3 != 0 {
+                            let mut input = input.into_bytes();
+                            input[last] = b'B';
+                            let input = alloc::string::String::from_utf8(input).unwrap();
+
+                            assert_eq!(Bytes::<$size>::from_base64(&input).unwrap(), zero);
+                            assert!(matches!(
+                                Bytes::<$size>::from_base64_strict(&input),
+                                Err(Error::NonCanonical)
+                            ));
+                        }
+                    }
                 }
             }
         };
     }
 
     test_size!(16);
+    test_size!(17);
     test_size!(32);
     test_size!(64);
     test_size!(128);
@@ -521,4 +571,8 @@ mod tests {
     test_size!(512);
     test_size!(1024);
     test_size!(2048);
+
+    // Sizes where the last SIMD chunk of the base64 input is only partially used
+    test_size!(46);
+    test_size!(70);
 }
@@ -0,0 +1,184 @@
+use core::ops::{Deref, DerefMut};
+
+use crate::{Error, ReprBytes, ReprSize};
+
+/// An integer encoded in big endian byte order.
+///
+/// Unsigned integers encoded this way sort the same as their bytes, which makes them suitable for
+/// keys in ordered key-value stores. Signed integers keep their two's complement form, so negative
+/// values sort after positive ones.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Be<T>(pub T);
+
+/// An integer encoded in little endian byte order, the same as the integer itself.
+///
+/// This makes the byte order explicit next to [`Be`] fields.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Le<T>(pub T);
+
+macro_rules! impl_wrapper {
+    ($wrapper:ident) => {
+        impl<T> $wrapper<T> {
+            pub fn into_inner(self) -> T {
+                self.0
+            }
+        }
+
+        impl<T> From<T> for $wrapper<T> {
+            fn from(value: T) -> Self {
+                Self(value)
+            }
+        }
+
+        impl<T> Deref for $wrapper<T> {
+            type Target = T;
+
+            fn deref(&self) -> &T {
+                &self.0
+            }
+        }
+
+        impl<T> DerefMut for $wrapper<T> {
+            fn deref_mut(&mut self) -> &mut T {
+                &mut self.0
+            }
+        }
+
+        #[cfg(any(test, feature = "proptest"))]
+        impl<T: proptest::prelude::Arbitrary + 'static> proptest::prelude::Arbitrary for $wrapper<T> {
+            type Parameters = T::Parameters;
+            type Strategy = proptest::strategy::Map<T::Strategy, fn(T) -> Self>;
+
+            fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+                use proptest::prelude::*;
+                any_with::<T>(args).prop_map(Self)
+            }
+        }
+    };
+}
+
+impl_wrapper!(Be);
+impl_wrapper!(Le);
+
+macro_rules! impl_repr_endian {
+    ($type:ty) => {
+        impl ReprSize for Be<$type> {
+            const SIZE: usize = core::mem::size_of::<$type>();
+        }
+
+        impl ReprBytes<{ core::mem::size_of::<$type>() }> for Be<$type> {
+            #[inline(always)]
+            fn from_bytes(input: [u8; core::mem::size_of::<$type>()]) -> Self {
+                Self(<$type>::from_be_bytes(input))
+            }
+
+            #[inline(always)]
+            fn as_bytes(&self) -> [u8; core::mem::size_of::<$type>()] {
+                self.0.to_be_bytes()
+            }
+        }
+
+        impl_repr_endian!(@le $type, $type);
+    };
+    // Pointer-sized integers are encoded as `$repr`, like the integers themselves.
+    ($type:ty as $repr:ty) => {
+        impl ReprSize for Be<$type> {
+            const SIZE: usize = core::mem::size_of::<$repr>();
+        }
+
+        impl ReprBytes<{ core::mem::size_of::<$repr>() }> for Be<$type> {
+            #[inline(always)]
+            fn from_bytes(input: [u8; core::mem::size_of::<$repr>()]) -> Self {
+                Self(<$repr>::from_be_bytes(input) as $type)
+            }
+
+            #[inline(always)]
+            fn as_bytes(&self) -> [u8; core::mem::size_of::<$repr>()] {
+                (self.0 as $repr).to_be_bytes()
+            }
+
+            #[inline(always)]
+            fn try_from_bytes(input: [u8; core::mem::size_of::<$repr>()]) -> Result<Self, Error> {
+                Ok(Self(<$type>::try_from(<$repr>::from_be_bytes(input))?))
+            }
+        }
+
+        impl_repr_endian!(@le $type, $repr);
+    };
+    (@le $type:ty, $repr:ty) => {
+        impl ReprSize for Le<$type> {
+            const SIZE: usize = core::mem::size_of::<$repr>();
+        }
+
+        impl ReprBytes<{ core::mem::size_of::<$repr>() }> for Le<$type> {
+            #[inline(always)]
+            fn from_bytes(input: [u8; core::mem::size_of::<$repr>()]) -> Self {
+                Self(<$type>::from_bytes(input))
+            }
+
+            #[inline(always)]
+            fn as_bytes(&self) -> [u8; core::mem::size_of::<$repr>()] {
+                self.0.as_bytes()
+            }
+
+            #[inline(always)]
+            fn try_from_bytes(input: [u8; core::mem::size_of::<$repr>()]) -> Result<Self, Error> {
+                Ok(Self(<$type>::try_from_bytes(input)?))
+            }
+        }
+    };
+}
+
+impl_repr_endian!(u8);
+impl_repr_endian!(u16);
+impl_repr_endian!(u32);
+impl_repr_endian!(u64);
+impl_repr_endian!(u128);
+impl_repr_endian!(i8);
+impl_repr_endian!(i16);
+impl_repr_endian!(i32);
+impl_repr_endian!(i64);
+impl_repr_endian!(i128);
+impl_repr_endian!(usize as u64);
+impl_repr_endian!(isize as i64);
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    macro_rules! generate_tests {
+        ($($type:ty),*) => {
+            paste::paste! {
+                $(
+                    #[test_strategy::proptest]
+                    fn [<test_roundtrip_ $type>](value: $type) {
+                        prop_assert_eq!(Be(value).as_bytes(), value.to_be_bytes());
+                        prop_assert_eq!(Le(value).as_bytes(), value.to_le_bytes());
+                        prop_assert_eq!(Be::<$type>::from_bytes_strict(Be(value).as_bytes())?, Be(value));
+                        prop_assert_eq!(Le::<$type>::from_bytes_strict(Le(value).as_bytes())?, Le(value));
+                    }
+                )*
+            }
+        };
+    }
+
+    generate_tests!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+    #[test_strategy::proptest]
+    fn test_ordering(a: u64, b: u64) {
+        let (a, b) = (Be(a), Be(b));
+        prop_assert_eq!(a.cmp(&b), a.as_bytes().cmp(&b.as_bytes()));
+    }
+
+    #[test]
+    fn test_pointer_sized() {
+        assert_eq!(Be(1usize).as_bytes(), [0, 0, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(Be(-2isize).as_bytes(), (-2i64).to_be_bytes());
+        assert_eq!(Le(1usize).as_bytes(), 1usize.as_bytes());
+        assert_eq!(*Be::<usize>::from_bytes([0, 0, 0, 0, 0, 0, 1, 0]), 256);
+    }
+}
@@ -1,11 +1,31 @@
 use alloc::string::{String, ToString};
-use core::{array::TryFromSliceError, fmt};
+use core::{array::TryFromSliceError, fmt, num::TryFromIntError};
 
 pub enum Error {
-    InvalidDataSize { expected: usize, got: usize },
+    InvalidDataSize {
+        expected: usize,
+        got: usize,
+    },
     InvalidHexDigit(char),
     InvalidBase64Character(char),
+    InvalidDiscriminant(u64),
+    InvalidBitWidth(usize),
+    NonCanonical,
+    CapacityExceeded(usize),
+    InvalidSet,
+    BitOverflow {
+        needed: usize,
+        available: usize,
+    },
+    NonZeroReserved,
+    /// A value was rejected by a `#[mucodec(validate = ...)]` hook. `field` names the field, or
+    /// the type for hooks on the whole value.
+    InvalidField {
+        field: &'static str,
+        reason: String,
+    },
     SliceConversionError(String),
+    IntConversionError(String),
 }
 
 impl fmt::Debug for Error {
@@ -20,7 +40,22 @@ impl fmt::Debug for Error {
             }
             Error::InvalidHexDigit(c) => write!(f, "InvalidHexDigit({})", c),
             Error::InvalidBase64Character(c) => write!(f, "InvalidBase64Character({})", c),
+            Error::InvalidDiscriminant(d) => write!(f, "InvalidDiscriminant({})", d),
+            Error::InvalidBitWidth(w) => write!(f, "InvalidBitWidth({})", w),
+            Error::NonCanonical => write!(f, "NonCanonical"),
+            Error::CapacityExceeded(capacity) => write!(f, "CapacityExceeded({})", capacity),
+            Error::InvalidSet => write!(f, "InvalidSet"),
+            Error::NonZeroReserved => write!(f, "NonZeroReserved"),
+            Error::InvalidField { field, reason } => {
+                write!(f, "InvalidField {{ field: {}, reason: {} }}", field, reason)
+            }
+            Error::BitOverflow { needed, available } => write!(
+                f,
+                "BitOverflow {{ needed: {}, available: {} }}",
+                needed, available
+            ),
             Error::SliceConversionError(msg) => write!(f, "SliceConversionError({})", msg),
+            Error::IntConversionError(msg) => write!(f, "IntConversionError({})", msg),
         }
     }
 }
@@ -33,7 +68,20 @@ impl fmt::Display for Error {
             }
             Error::InvalidHexDigit(c) => write!(f, "Invalid hex digit: {}", c),
             Error::InvalidBase64Character(c) => write!(f, "Invalid base64 character: {}", c),
+            Error::InvalidDiscriminant(d) => write!(f, "Invalid discriminant: {}", d),
+            Error::InvalidBitWidth(w) => write!(f, "Invalid bit width: {}", w),
+            Error::NonCanonical => write!(f, "Non-canonical encoding"),
+            Error::CapacityExceeded(capacity) => write!(f, "Capacity of {} exceeded", capacity),
+            Error::InvalidSet => write!(f, "Invalid set encoding"),
+            Error::NonZeroReserved => write!(f, "Reserved bits are not zero"),
+            Error::InvalidField { field, reason } => write!(f, "Invalid {}: {}", field, reason),
+            Error::BitOverflow { needed, available } => write!(
+                f,
+                "Bit overflow: needed {} bits, {} available",
+                needed, available
+            ),
             Error::SliceConversionError(msg) => write!(f, "Slice conversion error: {}", msg),
+            Error::IntConversionError(msg) => write!(f, "Integer conversion error: {}", msg),
         }
     }
 }
@@ -45,3 +93,9 @@ impl From<TryFromSliceError> for Error {
         Self::SliceConversionError(value.to_string())
     }
 }
+
+impl From<TryFromIntError> for Error {
+    fn from(value: TryFromIntError) -> Self {
+        Self::IntConversionError(value.to_string())
+    }
+}
@@ -0,0 +1,425 @@
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Sub, SubAssign},
+};
+
+use crate::{ReprBytes, ReprSize};
+
+/// A fieldless enum whose variants are the flags of a [`Flags`] set.
+///
+/// Use `#[derive(ReprFlags)]` to implement it: every variant takes the next bit, starting from the
+/// least significant one, unless it sets its own with `#[mucodec(bit = N)]`.
+pub trait ReprFlags: Copy + Eq + 'static {
+    /// Every flag, in declaration order.
+    const FLAGS: &'static [Self];
+
+    /// The encoded size of a set, the size of the smallest integer that holds every bit.
+    const SIZE: usize;
+
+    /// Returns the index of the bit used by this flag.
+    fn bit(self) -> u32;
+
+    /// Returns the name of this flag, as printed by the `Debug` implementation of [`Flags`].
+    fn name(self) -> &'static str;
+}
+
+/// A set of [`ReprFlags`], encoded as a little endian bitmask.
+///
+/// Decoding with [`ReprBytes::from_bytes`] ignores bits that do not belong to any flag, while
+/// [`ReprBytes::from_bytes_strict`] rejects them with [`Error::NonCanonical`](crate::Error).
+pub struct Flags<E> {
+    bits: u128,
+    _flags: PhantomData<E>,
+}
+
+impl<E: ReprFlags> Flags<E> {
+    const fn new(bits: u128) -> Self {
+        Self {
+            bits,
+            _flags: PhantomData,
+        }
+    }
+
+    /// Returns the set with no flags.
+    pub const fn empty() -> Self {
+        Self::new(0)
+    }
+
+    /// Returns the set with every flag.
+    pub fn all() -> Self {
+        Self::new(Self::mask())
+    }
+
+    /// Returns the bits of every flag.
+    fn mask() -> u128 {
+        E::FLAGS.iter().fold(0, |mask, flag| mask | 1 << flag.bit())
+    }
+
+    /// Returns the raw bitmask of the set.
+    pub fn bits(&self) -> u128 {
+        self.bits
+    }
+
+    /// Builds a set from a bitmask, returning `None` if any bit does not belong to a flag.
+    pub fn from_bits(bits: u128) -> Option<Self> {
+        (bits & !Self::mask() == 0).then_some(Self::new(bits))
+    }
+
+    /// Builds a set from a bitmask, dropping the bits that do not belong to a flag.
+    pub fn from_bits_truncate(bits: u128) -> Self {
+        Self::new(bits & Self::mask())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    pub fn is_all(&self) -> bool {
+        self.bits == Self::mask()
+    }
+
+    /// Returns the number of flags in the set.
+    pub fn len(&self) -> usize {
+        self.bits.count_ones() as usize
+    }
+
+    pub fn contains(&self, flag: E) -> bool {
+        self.bits & 1 << flag.bit() != 0
+    }
+
+    /// Returns whether every flag of `other` is also in this set.
+    pub fn contains_all(&self, other: Self) -> bool {
+        self.bits & other.bits == other.bits
+    }
+
+    /// Returns whether this set and `other` have any flag in common.
+    pub fn intersects(&self, other: Self) -> bool {
+        self.bits & other.bits != 0
+    }
+
+    /// Adds a flag, returning whether it was not already in the set.
+    pub fn insert(&mut self, flag: E) -> bool {
+        let inserted = !self.contains(flag);
+        self.bits |= 1 << flag.bit();
+        inserted
+    }
+
+    /// Removes a flag, returning whether it was in the set.
+    pub fn remove(&mut self, flag: E) -> bool {
+        let removed = self.contains(flag);
+        self.bits &= !(1 << flag.bit());
+        removed
+    }
+
+    /// Adds or removes a flag.
+    pub fn set(&mut self, flag: E, value: bool) {
+        if value {
+            self.insert(flag);
+        } else {
+            self.remove(flag);
+        }
+    }
+
+    pub fn toggle(&mut self, flag: E) {
+        self.bits ^= 1 << flag.bit();
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self::new(self.bits | other.bits)
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        Self::new(self.bits & other.bits)
+    }
+
+    pub fn difference(self, other: Self) -> Self {
+        Self::new(self.bits & !other.bits)
+    }
+
+    pub fn symmetric_difference(self, other: Self) -> Self {
+        Self::new(self.bits ^ other.bits)
+    }
+
+    /// Returns the flags that are not in the set.
+    pub fn complement(self) -> Self {
+        Self::new(!self.bits & Self::mask())
+    }
+
+    /// Iterates over the flags in the set, in declaration order.
+    pub fn iter(&self) -> impl Iterator<Item = E> + '_ {
+        E::FLAGS.iter().copied().filter(|&flag| self.contains(flag))
+    }
+}
+
+impl<E> Clone for Flags<E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E> Copy for Flags<E> {}
+
+impl<E> PartialEq for Flags<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bits == other.bits
+    }
+}
+
+impl<E> Eq for Flags<E> {}
+
+impl<E> Hash for Flags<E> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bits.hash(state);
+    }
+}
+
+impl<E: ReprFlags> Default for Flags<E> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<E: ReprFlags> fmt::Debug for Flags<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Flags(")?;
+
+        for (i, flag) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" | ")?;
+            }
+
+            f.write_str(flag.name())?;
+        }
+
+        f.write_str(")")
+    }
+}
+
+impl<E: ReprFlags> From<E> for Flags<E> {
+    fn from(flag: E) -> Self {
+        Self::new(1 << flag.bit())
+    }
+}
+
+impl<E: ReprFlags> FromIterator<E> for Flags<E> {
+    fn from_iter<I: IntoIterator<Item = E>>(iter: I) -> Self {
+        let mut flags = Self::empty();
+        flags.extend(iter);
+        flags
+    }
+}
+
+impl<E: ReprFlags> Extend<E> for Flags<E> {
+    fn extend<I: IntoIterator<Item = E>>(&mut self, iter: I) {
+        for flag in iter {
+            self.insert(flag);
+        }
+    }
+}
+
+macro_rules! impl_flags_op {
+    ($op:ident, $method:ident, $assign:ident, $assign_method:ident, $set:ident) => {
+        impl<E: ReprFlags> $op for Flags<E> {
+            type Output = Self;
+
+            fn $method(self, other: Self) -> Self {
+                self.$set(other)
+            }
+        }
+
+        impl<E: ReprFlags> $op<E> for Flags<E> {
+            type Output = Self;
+
+            fn $method(self, other: E) -> Self {
+                self.$set(other.into())
+            }
+        }
+
+        impl<E: ReprFlags> $assign for Flags<E> {
+            fn $assign_method(&mut self, other: Self) {
+                *self = self.$set(other);
+            }
+        }
+
+        impl<E: ReprFlags> $assign<E> for Flags<E> {
+            fn $assign_method(&mut self, other: E) {
+                *self = self.$set(other.into());
+            }
+        }
+    };
+}
+
+impl_flags_op!(BitOr, bitor, BitOrAssign, bitor_assign, union);
+impl_flags_op!(BitAnd, bitand, BitAndAssign, bitand_assign, intersection);
+impl_flags_op!(Sub, sub, SubAssign, sub_assign, difference);
+impl_flags_op!(
+    BitXor,
+    bitxor,
+    BitXorAssign,
+    bitxor_assign,
+    symmetric_difference
+);
+
+impl<E: ReprFlags> Not for Flags<E> {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        self.complement()
+    }
+}
+
+impl<E: ReprFlags> ReprSize for Flags<E> {
+    const SIZE: usize = E::SIZE;
+}
+
+impl<E: ReprFlags> ReprBytes<{ E::SIZE }> for Flags<E> {
+    /// Decodes a set, dropping the bits that do not belong to a flag.
+    fn from_bytes(input: [u8; E::SIZE]) -> Self {
+        let mut bytes = [0u8; 16];
+        bytes[..E::SIZE].copy_from_slice(&input);
+        Self::from_bits_truncate(u128::from_le_bytes(bytes))
+    }
+
+    fn as_bytes(&self) -> [u8; E::SIZE] {
+        let mut out = [0u8; E::SIZE];
+        out.copy_from_slice(&self.bits.to_le_bytes()[..E::SIZE]);
+        out
+    }
+}
+
+#[cfg(any(test, feature = "proptest"))]
+impl<E: ReprFlags> proptest::prelude::Arbitrary for Flags<E> {
+    type Parameters = ();
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        use proptest::prelude::*;
+        any::<u128>().prop_map(Self::from_bits_truncate).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::Error;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    enum Color {
+        Red,
+        Green,
+        Blue,
+    }
+
+    impl ReprFlags for Color {
+        const FLAGS: &'static [Self] = &[Self::Red, Self::Green, Self::Blue];
+        const SIZE: usize = 1;
+
+        fn bit(self) -> u32 {
+            match self {
+                Self::Red => 0,
+                Self::Green => 1,
+                Self::Blue => 4,
+            }
+        }
+
+        fn name(self) -> &'static str {
+            match self {
+                Self::Red => "Red",
+                Self::Green => "Green",
+                Self::Blue => "Blue",
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    enum Wide {
+        Low,
+        High,
+    }
+
+    impl ReprFlags for Wide {
+        const FLAGS: &'static [Self] = &[Self::Low, Self::High];
+        const SIZE: usize = 16;
+
+        fn bit(self) -> u32 {
+            match self {
+                Self::Low => 0,
+                Self::High => 127,
+            }
+        }
+
+        fn name(self) -> &'static str {
+            match self {
+                Self::Low => "Low",
+                Self::High => "High",
+            }
+        }
+    }
+
+    #[test_strategy::proptest]
+    fn test_roundtrip(flags: Flags<Color>, wide: Flags<Wide>) {
+        prop_assert_eq!(Flags::from_bytes_strict(flags.as_bytes())?, flags);
+        prop_assert_eq!(Flags::from_bytes_strict(wide.as_bytes())?, wide);
+    }
+
+    #[test_strategy::proptest]
+    fn test_set_ops(a: Flags<Color>, b: Flags<Color>) {
+        prop_assert_eq!((a | b).len(), a.len() + b.len() - (a & b).len());
+        prop_assert_eq!(a - b, a & !b);
+        prop_assert_eq!(a ^ b, (a | b) - (a & b));
+        prop_assert_eq!(a.iter().collect::<Flags<_>>(), a);
+        prop_assert!((a | b).contains_all(a));
+        prop_assert_eq!((a & b).is_empty(), !a.intersects(b));
+        prop_assert!((a | !a).is_all());
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut flags = Flags::<Color>::empty();
+
+        assert!(flags.insert(Color::Blue));
+        assert!(!flags.insert(Color::Blue));
+        flags |= Color::Red;
+        assert_eq!(flags.bits(), 0b10001);
+        assert_eq!(flags.iter().collect::<Vec<_>>(), [Color::Red, Color::Blue]);
+
+        assert!(flags.remove(Color::Red));
+        assert!(!flags.remove(Color::Red));
+        flags.set(Color::Green, true);
+        flags.toggle(Color::Blue);
+        assert_eq!(flags, Color::Green.into());
+        assert_eq!(Flags::<Color>::from_bits(0b1000), None);
+        assert_eq!(
+            Flags::<Color>::from_bits_truncate(0b1010),
+            Color::Green.into()
+        );
+    }
+
+    #[test]
+    fn test_unknown_bits() {
+        assert_eq!(Flags::<Color>::from_bytes([0xFF]), Flags::all());
+        assert!(matches!(
+            Flags::<Color>::from_bytes_strict([0b1000]),
+            Err(Error::NonCanonical)
+        ));
+
+        let mut bytes = [0u8; 16];
+        bytes[15] = 0xC0;
+        assert_eq!(Flags::<Wide>::from_bytes(bytes), Wide::High.into());
+        assert!(Flags::<Wide>::from_bytes_strict(bytes).is_err());
+    }
+
+    #[test]
+    fn test_debug() {
+        assert_eq!(format!("{:?}", Flags::<Color>::empty()), "Flags()");
+        assert_eq!(
+            format!("{:?}", Flags::<Color>::all()),
+            "Flags(Red | Green | Blue)"
+        );
+        assert_eq!(format!("{:?}", Flags::from(Wide::High)), "Flags(High)");
+    }
+}
@@ -5,8 +5,11 @@
 
 extern crate alloc;
 
+mod bits;
 mod bytes;
+mod endian;
 mod error;
+mod flags;
 mod list;
 mod repr;
 mod string;
@@ -17,4 +20,19 @@ extern crate mucodec_derive;
 #[cfg(feature = "derive")]
 pub use mucodec_derive::*;
 
-pub use self::{bytes::Bytes, error::Error, list::*, repr::*, string::*};
+pub use self::{
+    bits::{BitReader, BitWriter},
+    bytes::Bytes,
+    endian::{Be, Le},
+    error::Error,
+    flags::{Flags, ReprFlags},
+    list::*,
+    repr::*,
+    string::*,
+};
+
+/// Re-exports used by the code generated by the derive macros.
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::{vec, vec::Vec};
+}
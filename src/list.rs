@@ -1,211 +1,1458 @@
 use alloc::vec::Vec;
 use core::{
     fmt,
-    hash::{Hash, Hasher},
+    hash::Hash,
     mem::size_of,
-    ops::Deref,
-    simd::*,
+    ops::{Deref, DerefMut, Index, IndexMut},
+    simd::{num::SimdUint, *},
+    slice::{self, SliceIndex},
 };
 
 use crate::*;
 
-macro_rules! impl_list {
-    ($type:ty, $list_type:ident, $simd_type:ty, $lanes:ty) => {
-        #[derive(Clone, Copy, PartialEq, Eq)]
-        #[repr(transparent)]
-        pub struct $list_type<const N: usize>([$type; N]);
+/// Header of lists packed with [`PackMode::Delta`].
+const DELTA_HEADER: usize = 0xF1;
 
-        impl<const N: usize> Hash for $list_type<N> {
-            fn hash<H: Hasher>(&self, state: &mut H) {
-                self.0.hash(state);
+/// Header of lists packed with [`PackMode::DeltaOfDelta`].
+const DELTA_OF_DELTA_HEADER: usize = 0xF2;
+
+/// Header of lists packed with [`PackMode::Blocks`].
+const BLOCKS_HEADER: usize = 0xF3;
+
+/// Header of lists packed with [`PackMode::Sparse`].
+const SPARSE_HEADER: usize = 0xF4;
+
+/// Number of values in each block of [`PackMode::Blocks`].
+pub const BLOCK_SIZE: usize = 128;
+
+/// How a list is packed by [`ReprPacked::pack`].
+///
+/// The header returned when packing identifies the mode. Headers up to the bit size of the element
+/// type are the bit width of a [`PackMode::Plain`] list, and the modes use reserved values above
+/// that.
+///
+/// The modes other than [`PackMode::Plain`] only shrink the output of [`ReprPacked::pack`] and
+/// [`ReprPacked::to_compact`]. [`ReprBytes::as_bytes`] has a fixed size that every list fits in
+/// with the plain mode, so it always uses it, although decoding accepts every mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PackMode {
+    /// Every value is packed with the bit width of the largest one.
+    Plain,
+    /// The first value is stored as is, followed by the packed differences between consecutive
+    /// values. Works best for sorted lists.
+    Delta,
+    /// The first value and difference are stored as is, followed by the packed differences
+    /// between consecutive differences. Works best for lists that grow at a steady rate, like
+    /// timestamps.
+    DeltaOfDelta,
+    /// Values are split into blocks of [`BLOCK_SIZE`], each packed as the difference from the
+    /// smallest value in the block with its own bit width. Keeps a few outliers from widening the
+    /// whole list. Like the other modes, it is never used by [`ReprBytes::as_bytes`].
+    Blocks,
+    /// A bitmap marks the non-zero values, which are packed on their own. Works best for lists
+    /// that are mostly zeros.
+    Sparse,
+}
+
+/// Writes `values`, the elements starting at `index`, into the bit stream stored in `words`.
+///
+/// Every lane computes its own word and shift, and values that cross a word boundary are split
+/// into a low part and a high part for the next word. `bit_width` must be non-zero.
+#[inline(always)]
+fn pack_lanes<const L: usize>(
+    words: &mut [u64],
+    index: usize,
+    bit_width: usize,
+    values: Simd<u64, L>,
+) {
+    let offsets = Simd::from_array(core::array::from_fn(|i| i as u64));
+    let positions = (Simd::splat(index as u64) + offsets) * Simd::splat(bit_width as u64);
+    let shifts = positions & Simd::splat(63);
+    let indices = (positions >> 6).to_array();
+
+    // Shifting by 64 is not allowed, so the high part is shifted in two steps
+    let lo = (values << shifts).to_array();
+    let hi = ((values >> Simd::splat(1)) >> (Simd::splat(63) - shifts)).to_array();
+
+    for i in 0..L {
+        let index = indices[i] as usize;
+        words[index] |= lo[i];
+
+        if let Some(word) = words.get_mut(index + 1) {
+            *word |= hi[i];
+        }
+    }
+}
+
+/// Reads `L` elements starting at `index` from the bit stream stored in `words`.
+#[inline(always)]
+fn unpack_lanes<const L: usize>(words: &[u64], index: usize, bit_width: usize) -> Simd<u64, L> {
+    let offsets = Simd::from_array(core::array::from_fn(|i| i as u64));
+    let positions = (Simd::splat(index as u64) + offsets) * Simd::splat(bit_width as u64);
+    let shifts = positions & Simd::splat(63);
+    let indices = (positions >> 6).cast::<usize>();
+    let mask = u64::MAX
+        .checked_shr(u64::BITS - bit_width as u32)
+        .unwrap_or(0);
+
+    let lo = Simd::gather_or_default(words, indices);
+    let hi = Simd::gather_or_default(words, indices + Simd::splat(1));
+
+    ((lo >> shifts) | ((hi << Simd::splat(1)) << (Simd::splat(63) - shifts))) & Simd::splat(mask)
+}
+
+/// An unsigned integer type that list values are packed as.
+///
+/// Implementations pick the SIMD kernels for their width. Types without a SIMD vector, like
+/// `u128`, are packed one value at a time.
+pub trait PackedInt: Copy + Default + Ord + Hash + fmt::Debug {
+    const BITS: u32;
+
+    /// Returns the number of bits needed by the largest value.
+    fn bit_width(values: &[Self]) -> usize;
+
+    /// Writes values as a little-endian bit stream, least significant bit first, so n values take
+    /// exactly ceil(n * bit_width / 8) bytes.
+    fn pack_values(values: &[Self], bit_width: usize) -> Vec<u8>;
+
+    /// Reads `out.len()` values written by [`PackedInt::pack_values`].
+    fn unpack_values(bit_width: usize, input: &[u8], out: &mut [Self]);
+
+    /// Converts from a `u128`, keeping only the low bits.
+    fn from_u128(value: u128) -> Self;
+
+    fn to_u128(self) -> u128;
+
+    fn wrapping_add(self, rhs: Self) -> Self;
+
+    fn wrapping_sub(self, rhs: Self) -> Self;
+
+    /// Zigzag encodes a value read as two's complement, mapping 0, -1, 1, -2, 2, ... to 0, 1, 2,
+    /// 3, 4, ..., so small negative values pack as tightly as small positive ones.
+    fn zigzag(self) -> Self;
+
+    fn unzigzag(self) -> Self;
+}
+
+/// A type that can be stored in a [`List`].
+pub trait ListElement: Copy + Default + Eq + Hash + fmt::Debug {
+    /// The unsigned type values are packed as.
+    type Packed: PackedInt;
+
+    /// Whether values are zigzag encoded before packing.
+    const ZIGZAG: bool = false;
+
+    /// Returns the two's complement representation of the value.
+    fn to_bits(self) -> Self::Packed;
+
+    /// Reverses [`ListElement::to_bits`], failing for bits that do not represent a value.
+    fn from_bits(bits: Self::Packed) -> Result<Self, Error>;
+
+    /// Maps a value to the unsigned value that is packed in its place.
+    #[inline(always)]
+    fn encode(self) -> Self::Packed {
+        if Self::ZIGZAG {
+            self.to_bits().zigzag()
+        } else {
+            self.to_bits()
+        }
+    }
+
+    /// Maps a packed value back to the value it represents.
+    #[inline(always)]
+    fn decode(packed: Self::Packed) -> Result<Self, Error> {
+        Self::from_bits(if Self::ZIGZAG {
+            packed.unzigzag()
+        } else {
+            packed
+        })
+    }
+
+    /// Returns the number of bits needed by the packed value.
+    #[inline(always)]
+    fn bit_width(self) -> usize {
+        Self::Packed::bit_width(&[self.encode()])
+    }
+}
+
+macro_rules! impl_packed_int {
+    ($type:ty) => {
+        impl PackedInt for $type {
+            impl_packed_int!(@common $type);
+
+            fn bit_width(values: &[Self]) -> usize {
+                let acc = values.iter().fold(0, |acc, &value| acc | value);
+                (<$type>::BITS - acc.leading_zeros()) as usize
+            }
+
+            fn pack_values(values: &[Self], bit_width: usize) -> Vec<u8> {
+                pack_scalar(values, bit_width)
+            }
+
+            fn unpack_values(bit_width: usize, input: &[u8], out: &mut [Self]) {
+                unpack_scalar(bit_width, input, out)
+            }
+        }
+    };
+    ($type:ty, $simd_type:ty) => {
+        impl PackedInt for $type {
+            impl_packed_int!(@common $type);
+
+            fn bit_width(values: &[Self]) -> usize {
+                // The OR of all values has the same highest set bit as their maximum
+                let (chunks, remainder) = values.as_chunks::<{ <$simd_type>::LEN }>();
+                let mut acc = <$simd_type>::splat(0);
+
+                for chunk in chunks {
+                    acc |= <$simd_type>::from_array(*chunk);
+                }
+
+                let acc = remainder.iter().fold(acc.reduce_or(), |acc, &value| acc | value);
+                (<$type>::BITS - acc.leading_zeros()) as usize
+            }
+
+            fn pack_values(values: &[Self], bit_width: usize) -> Vec<u8> {
+                if values.len() < <$simd_type>::LEN {
+                    return pack_scalar(values, bit_width);
+                }
+
+                if bit_width == 0 {
+                    return Vec::new();
+                }
+
+                let mut words = alloc::vec![0u64; (values.len() * bit_width).div_ceil(64)];
+                let (chunks, remainder) = values.as_chunks::<{ <$simd_type>::LEN }>();
+                let offset = chunks.len() * <$simd_type>::LEN;
+
+                for (i, chunk) in chunks.iter().enumerate() {
+                    let values = <$simd_type>::from_array(*chunk).cast::<u64>();
+                    pack_lanes(&mut words, i * <$simd_type>::LEN, bit_width, values);
+                }
+
+                for (i, &value) in remainder.iter().enumerate() {
+                    pack_lanes::<1>(&mut words, offset + i, bit_width, Simd::splat(value as u64));
+                }
+
+                words
+                    .iter()
+                    .flat_map(|word| word.to_le_bytes())
+                    .take((values.len() * bit_width).div_ceil(8))
+                    .collect()
+            }
+
+            fn unpack_values(bit_width: usize, input: &[u8], out: &mut [Self]) {
+                if out.len() < <$simd_type>::LEN {
+                    return unpack_scalar(bit_width, input, out);
+                }
+
+                if bit_width == 0 {
+                    out.fill(0);
+                    return;
+                }
+
+                let words = input
+                    .chunks(8)
+                    .map(|chunk| {
+                        let mut bytes = [0u8; 8];
+                        bytes[..chunk.len()].copy_from_slice(chunk);
+                        u64::from_le_bytes(bytes)
+                    })
+                    .collect::<Vec<_>>();
+
+                let (chunks, remainder) = out.as_chunks_mut::<{ <$simd_type>::LEN }>();
+                let offset = chunks.len() * <$simd_type>::LEN;
+
+                for (i, chunk) in chunks.iter_mut().enumerate() {
+                    let values = unpack_lanes(&words, i * <$simd_type>::LEN, bit_width);
+                    *chunk = values.cast::<$type>().to_array();
+                }
+
+                for (i, value) in remainder.iter_mut().enumerate() {
+                    *value = unpack_lanes::<1>(&words, offset + i, bit_width)[0] as $type;
+                }
+            }
+        }
+    };
+    (@common $type:ty) => {
+        const BITS: u32 = <$type>::BITS;
+
+        #[inline(always)]
+        fn from_u128(value: u128) -> Self {
+            value as $type
+        }
+
+        #[inline(always)]
+        fn to_u128(self) -> u128 {
+            self as u128
+        }
+
+        #[inline(always)]
+        fn wrapping_add(self, rhs: Self) -> Self {
+            <$type>::wrapping_add(self, rhs)
+        }
+
+        #[inline(always)]
+        fn wrapping_sub(self, rhs: Self) -> Self {
+            <$type>::wrapping_sub(self, rhs)
+        }
+
+        #[inline(always)]
+        fn zigzag(self) -> Self {
+            (self << 1) ^ (self >> (<$type>::BITS - 1)).wrapping_neg()
+        }
+
+        #[inline(always)]
+        fn unzigzag(self) -> Self {
+            (self >> 1) ^ (self & 1).wrapping_neg()
+        }
+    };
+}
+
+impl_packed_int!(u8, u8x8);
+impl_packed_int!(u16, u16x4);
+impl_packed_int!(u32, u32x4);
+impl_packed_int!(u64, u64x2);
+impl_packed_int!(u128);
+
+macro_rules! impl_list_element {
+    ($type:ty) => {
+        impl ListElement for $type {
+            type Packed = $type;
+
+            #[inline(always)]
+            fn to_bits(self) -> $type {
+                self
+            }
+
+            #[inline(always)]
+            fn from_bits(bits: $type) -> Result<Self, Error> {
+                Ok(bits)
+            }
+        }
+    };
+    // Signed values are zigzag encoded before packing, so small negative values pack as tightly as
+    // small positive ones.
+    ($type:ty as $unsigned:ty) => {
+        impl ListElement for $type {
+            type Packed = $unsigned;
+
+            const ZIGZAG: bool = true;
+
+            #[inline(always)]
+            fn to_bits(self) -> $unsigned {
+                self as $unsigned
+            }
+
+            #[inline(always)]
+            fn from_bits(bits: $unsigned) -> Result<Self, Error> {
+                Ok(bits as $type)
+            }
+        }
+    };
+}
+
+impl_list_element!(u8);
+impl_list_element!(u16);
+impl_list_element!(u32);
+impl_list_element!(u64);
+impl_list_element!(u128);
+impl_list_element!(i8 as u8);
+impl_list_element!(i16 as u16);
+impl_list_element!(i32 as u32);
+impl_list_element!(i64 as u64);
+impl_list_element!(i128 as u128);
+
+impl ListElement for bool {
+    type Packed = u8;
+
+    #[inline(always)]
+    fn to_bits(self) -> u8 {
+        self as u8
+    }
+
+    fn from_bits(bits: u8) -> Result<Self, Error> {
+        match bits {
+            0 => Ok(false),
+            1 => Ok(true),
+            bits => Err(Error::InvalidDiscriminant(bits as u64)),
+        }
+    }
+}
+
+fn pack_scalar<T: PackedInt>(values: &[T], bit_width: usize) -> Vec<u8> {
+    let mut out = alloc::vec![0u8; (values.len() * bit_width).div_ceil(8)];
+    let mut writer = BitWriter::new(&mut out);
+
+    for &value in values {
+        let value = value.to_u128();
+
+        // Values wider than 64 bits are written in two halves
+        for (part, width) in [
+            (value as u64, bit_width.min(64)),
+            ((value >> 64) as u64, bit_width.saturating_sub(64)),
+        ] {
+            writer
+                .write(part, width)
+                .expect("output sized for the values");
+        }
+    }
+
+    out
+}
+
+/// Reads `out.len()` values written by [`pack_scalar`]. `input` must hold all of them.
+fn unpack_scalar<T: PackedInt>(bit_width: usize, input: &[u8], out: &mut [T]) {
+    let mut reader = BitReader::new(input);
+
+    for value in out.iter_mut() {
+        let mut bits = 0u128;
+
+        for (shift, width) in [(0, bit_width.min(64)), (64, bit_width.saturating_sub(64))] {
+            let part = reader.read(width).expect("input sized for the values");
+            bits |= (part as u128) << shift;
+        }
+
+        *value = T::from_u128(bits);
+    }
+}
+
+/// Appends a value as a little-endian integer of its own size.
+fn write_le<T: PackedInt>(out: &mut Vec<u8>, value: T) {
+    out.extend_from_slice(&value.to_u128().to_le_bytes()[..size_of::<T>()]);
+}
+
+/// Reads a value written by [`write_le`].
+fn read_le<T: PackedInt>(input: &[u8]) -> T {
+    let mut bytes = [0u8; 16];
+    bytes[..size_of::<T>()].copy_from_slice(input);
+    T::from_u128(u128::from_le_bytes(bytes))
+}
+
+/// A fixed-size list of `N` values, packed to the bits they need.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct List<T, const N: usize>([T; N]);
+
+pub type ListU8<const N: usize> = List<u8, N>;
+pub type ListU16<const N: usize> = List<u16, N>;
+pub type ListU32<const N: usize> = List<u32, N>;
+pub type ListU64<const N: usize> = List<u64, N>;
+pub type ListU128<const N: usize> = List<u128, N>;
+pub type ListI8<const N: usize> = List<i8, N>;
+pub type ListI16<const N: usize> = List<i16, N>;
+pub type ListI32<const N: usize> = List<i32, N>;
+pub type ListI64<const N: usize> = List<i64, N>;
+pub type ListI128<const N: usize> = List<i128, N>;
+
+impl<T: ListElement, const N: usize> List<T, N> {
+    /// The largest size of a list encoded with [`ReprPacked::to_compact`], for sizing buffers.
+    ///
+    /// No packing mode is picked over [`PackMode::Plain`] unless it is smaller, so the payload
+    /// never exceeds every value at its full width.
+    pub const MAX_COMPACT_SIZE: usize =
+        1 + varint_len(size_of::<T::Packed>() * N) + size_of::<T::Packed>() * N;
+
+    pub fn zero() -> Self {
+        Self([T::default(); N])
+    }
+
+    #[cfg(feature = "rand")]
+    pub fn random<R: rand::prelude::Rng>(rng: &mut R) -> Self
+    where
+        rand::distributions::Standard: rand::distributions::Distribution<T>,
+    {
+        let mut out = [T::default(); N];
+
+        for value in out.iter_mut() {
+            *value = rng.gen::<T>();
+        }
+
+        Self(out)
+    }
+
+    /// Builds a list from an iterator, failing unless it yields exactly `N` values.
+    pub fn try_from_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self, Error> {
+        let mut out = [T::default(); N];
+        let mut iter = iter.into_iter();
+        let mut len = 0;
+
+        for (value, item) in out.iter_mut().zip(iter.by_ref()) {
+            *value = item;
+            len += 1;
+        }
+
+        let extra = iter.count();
+        if len != N || extra > 0 {
+            return Err(Error::InvalidDataSize {
+                expected: N,
+                got: len + extra,
+            });
+        }
+
+        Ok(Self(out))
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.0.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, T> {
+        self.0.iter_mut()
+    }
+
+    /// Returns a list with `f` applied to every value.
+    pub fn map<U: ListElement, F: FnMut(T) -> U>(self, f: F) -> List<U, N> {
+        List(self.0.map(f))
+    }
+
+    pub fn into_array(self) -> [T; N] {
+        self.0
+    }
+
+    /// Packs the list using `mode`, returning the header that identifies the mode along with the
+    /// packed data.
+    ///
+    /// [`ReprPacked::pack`] uses whichever mode gives the smallest output.
+    pub fn pack_with(&self, mode: PackMode) -> (usize, Vec<u8>) {
+        match mode {
+            PackMode::Plain => {
+                let values = self.0.map(T::encode);
+                let bit_width = T::Packed::bit_width(&values);
+
+                (bit_width, T::Packed::pack_values(&values, bit_width))
+            }
+            PackMode::Delta => (DELTA_HEADER, self.pack_deltas(1)),
+            PackMode::DeltaOfDelta => (DELTA_OF_DELTA_HEADER, self.pack_deltas(2)),
+            PackMode::Blocks => (BLOCKS_HEADER, self.pack_blocks()),
+            PackMode::Sparse => (SPARSE_HEADER, self.pack_sparse()),
+        }
+    }
+
+    /// Returns the number of bytes [`Self::pack_with`] gives for every mode, computed from the bit
+    /// widths in a single pass over the list without packing it.
+    fn packed_sizes(&self) -> [(PackMode, usize); 5] {
+        let width = |value: T::Packed| T::Packed::bit_width(&[value]);
+        let zero = T::Packed::default();
+
+        // Largest value packed by each mode, which has the same bit width as all of them together
+        let (mut plain, mut delta, mut delta_of_delta, mut sparse) = (zero, zero, zero, zero);
+        let (mut previous, mut previous_delta) = (zero, zero);
+        let (mut block_min, mut block_max) = (zero, zero);
+        let mut blocks = N.div_ceil(BLOCK_SIZE) * (1 + size_of::<T::Packed>());
+        let mut present = 0;
+
+        for (i, value) in self.0.into_iter().enumerate() {
+            let encoded = value.encode();
+            plain = plain.max(encoded);
+
+            let bits = value.to_bits();
+            let difference = bits.wrapping_sub(previous);
+            if i >= 1 {
+                delta = delta.max(difference.zigzag());
+            }
+            if i >= 2 {
+                delta_of_delta = delta_of_delta.max(difference.wrapping_sub(previous_delta).zigzag());
+            }
+            (previous, previous_delta) = (bits, difference);
+
+            if i % BLOCK_SIZE == 0 {
+                (block_min, block_max) = (encoded, encoded);
+            } else {
+                (block_min, block_max) = (block_min.min(encoded), block_max.max(encoded));
+            }
+            if i % BLOCK_SIZE == BLOCK_SIZE - 1 || i == N - 1 {
+                let block_len = i % BLOCK_SIZE + 1;
+                blocks += (block_len * width(block_max.wrapping_sub(block_min))).div_ceil(8);
+            }
+
+            if encoded != zero {
+                present += 1;
+                sparse = sparse.max(encoded.wrapping_sub(T::Packed::from_u128(1)));
+            }
+        }
+
+        let deltas = |order: usize, max: T::Packed| {
+            let seeds = order.min(N);
+            1 + seeds * size_of::<T::Packed>() + ((N - seeds) * width(max)).div_ceil(8)
+        };
+
+        [
+            (PackMode::Plain, (N * width(plain)).div_ceil(8)),
+            (PackMode::Delta, deltas(1, delta)),
+            (PackMode::DeltaOfDelta, deltas(2, delta_of_delta)),
+            (PackMode::Blocks, blocks),
+            (
+                PackMode::Sparse,
+                N.div_ceil(8) + 1 + (present * width(sparse)).div_ceil(8),
+            ),
+        ]
+    }
+
+    /// Maps packed values back to the values they represent with `decode`.
+    fn decode_values(
+        values: [T::Packed; N],
+        decode: fn(T::Packed) -> Result<T, Error>,
+    ) -> Result<Self, Error> {
+        let mut out = [T::default(); N];
+
+        for (out, value) in out.iter_mut().zip(values) {
+            *out = decode(value)?;
+        }
+
+        Ok(Self(out))
+    }
+
+    /// Returns the number of bytes taken by data packed with `header`, reading the residue bit
+    /// width from `input` for the delta modes.
+    fn packed_len(header: usize, input: &[u8]) -> Result<usize, Error> {
+        let order = match header {
+            DELTA_HEADER => 1,
+            DELTA_OF_DELTA_HEADER => 2,
+            BLOCKS_HEADER => return Self::blocks_len(input),
+            SPARSE_HEADER => return Self::sparse_len(input),
+            bit_width if bit_width <= T::Packed::BITS as usize => {
+                return Ok((N * bit_width).div_ceil(8));
+            }
+            header => return Err(Error::InvalidBitWidth(header)),
+        };
+
+        let bit_width = match input.first() {
+            Some(&bit_width) => bit_width as usize,
+            None => {
+                return Err(Error::InvalidDataSize {
+                    expected: 1,
+                    got: 0,
+                })
+            }
+        };
+        if bit_width > T::Packed::BITS as usize {
+            return Err(Error::InvalidBitWidth(bit_width));
+        }
+
+        let seeds = order.min(N);
+        Ok(1 + seeds * size_of::<T::Packed>() + ((N - seeds) * bit_width).div_ceil(8))
+    }
+
+    /// Packs the differences of order `order` between consecutive values, laid out as
+    /// `[bit width][seed values][packed residues]`.
+    ///
+    /// The seeds are the first value and, for higher orders, the first differences, stored as
+    /// little-endian integers. Differences are taken on the two's complement representation of
+    /// the values and wrap on overflow.
+    fn pack_deltas(&self, order: usize) -> Vec<u8> {
+        let mut values = self.0.map(T::to_bits);
+        for round in 0..order {
+            for i in (round + 1..N).rev() {
+                values[i] = values[i].wrapping_sub(values[i - 1]);
+            }
+        }
+
+        let seeds = order.min(N);
+        let residues = values[seeds..]
+            .iter()
+            .map(|&value| value.zigzag())
+            .collect::<Vec<_>>();
+        let bit_width = T::Packed::bit_width(&residues);
+
+        let mut out = Vec::with_capacity(
+            1 + seeds * size_of::<T::Packed>() + (residues.len() * bit_width).div_ceil(8),
+        );
+        out.push(bit_width as u8);
+        for &value in &values[..seeds] {
+            write_le(&mut out, value);
+        }
+        out.extend(T::Packed::pack_values(&residues, bit_width));
+
+        out
+    }
+
+    /// Reverses [`Self::pack_deltas`]. `input` must have the size given by [`Self::packed_len`].
+    fn unpack_deltas(order: usize, input: &[u8]) -> Result<Self, Error> {
+        let seeds = order.min(N);
+        let bit_width = input[0] as usize;
+        let (seed_bytes, residues) = input[1..].split_at(seeds * size_of::<T::Packed>());
+
+        let mut values = [T::Packed::default(); N];
+        for (value, bytes) in values
+            .iter_mut()
+            .zip(seed_bytes.chunks_exact(size_of::<T::Packed>()))
+        {
+            *value = read_le(bytes);
+        }
+
+        T::Packed::unpack_values(bit_width, residues, &mut values[seeds..]);
+        for value in values[seeds..].iter_mut() {
+            *value = value.unzigzag();
+        }
+
+        for round in (0..order).rev() {
+            for i in round + 1..N {
+                values[i] = values[i].wrapping_add(values[i - 1]);
+            }
+        }
+
+        Self::decode_values(values, T::from_bits)
+    }
+
+    /// Returns the number of bytes taken by data packed with [`PackMode::Blocks`], reading the
+    /// block header table from `input`.
+    fn blocks_len(input: &[u8]) -> Result<usize, Error> {
+        let entry_size = 1 + size_of::<T::Packed>();
+        let table_size = N.div_ceil(BLOCK_SIZE) * entry_size;
+        if input.len() < table_size {
+            return Err(Error::InvalidDataSize {
+                expected: table_size,
+                got: input.len(),
+            });
+        }
+
+        let mut size = table_size;
+        for (i, entry) in input[..table_size].chunks_exact(entry_size).enumerate() {
+            let bit_width = entry[0] as usize;
+            if bit_width > T::Packed::BITS as usize {
+                return Err(Error::InvalidBitWidth(bit_width));
+            }
+
+            let block_len = (N - i * BLOCK_SIZE).min(BLOCK_SIZE);
+            size += (block_len * bit_width).div_ceil(8);
+        }
+
+        Ok(size)
+    }
+
+    /// Packs the list in blocks of [`BLOCK_SIZE`] values, laid out as a header table with
+    /// `[bit width][base]` for every block, followed by the packed blocks.
+    ///
+    /// Each block stores its values as the difference from its base, the smallest value in the
+    /// block, and every block starts on a byte boundary.
+    fn pack_blocks(&self) -> Vec<u8> {
+        let values = self.0.map(T::encode);
+        let mut table = Vec::with_capacity(N.div_ceil(BLOCK_SIZE) * (1 + size_of::<T::Packed>()));
+        let mut data = Vec::new();
+
+        for block in values.chunks(BLOCK_SIZE) {
+            let base = block.iter().copied().min().unwrap_or_default();
+            let residues = block
+                .iter()
+                .map(|&value| value.wrapping_sub(base))
+                .collect::<Vec<_>>();
+            let bit_width = T::Packed::bit_width(&residues);
+
+            table.push(bit_width as u8);
+            write_le(&mut table, base);
+            data.extend(T::Packed::pack_values(&residues, bit_width));
+        }
+
+        table.extend(data);
+        table
+    }
+
+    /// Reverses [`Self::pack_blocks`]. `input` must have the size given by [`Self::packed_len`].
+    fn unpack_blocks(input: &[u8]) -> Result<Self, Error> {
+        let entry_size = 1 + size_of::<T::Packed>();
+        let (table, mut data) = input.split_at(N.div_ceil(BLOCK_SIZE) * entry_size);
+        let mut values = [T::Packed::default(); N];
+
+        for (block, entry) in values
+            .chunks_mut(BLOCK_SIZE)
+            .zip(table.chunks_exact(entry_size))
+        {
+            let bit_width = entry[0] as usize;
+            let base = read_le::<T::Packed>(&entry[1..]);
+            let (packed, rest) = data.split_at((block.len() * bit_width).div_ceil(8));
+
+            T::Packed::unpack_values(bit_width, packed, block);
+            for value in block.iter_mut() {
+                *value = value.wrapping_add(base);
+            }
+
+            data = rest;
+        }
+
+        Self::decode_values(values, T::decode)
+    }
+
+    /// Returns the number of bytes taken by data packed with [`PackMode::Sparse`], reading the
+    /// bitmap and the bit width from `input`.
+    fn sparse_len(input: &[u8]) -> Result<usize, Error> {
+        let bitmap_size = N.div_ceil(8);
+        if input.len() <= bitmap_size {
+            return Err(Error::InvalidDataSize {
+                expected: bitmap_size + 1,
+                got: input.len(),
+            });
+        }
+
+        let bit_width = input[bitmap_size] as usize;
+        if bit_width > T::Packed::BITS as usize {
+            return Err(Error::InvalidBitWidth(bit_width));
+        }
+
+        let present = (0..N)
+            .filter(|&i| input[i / 8] & (1 << (i % 8)) != 0)
+            .count();
+        Ok(bitmap_size + 1 + (present * bit_width).div_ceil(8))
+    }
+
+    /// Packs the non-zero values of the list, laid out as `[bitmap][bit width][packed values]`.
+    ///
+    /// Bit `i` of the bitmap, least significant bit first, is set when value `i` is not zero.
+    /// Non-zero values are stored minus one, so there is a single encoding for every list.
+    fn pack_sparse(&self) -> Vec<u8> {
+        let mut out = alloc::vec![0u8; N.div_ceil(8)];
+        let mut present = Vec::new();
+
+        for (i, value) in self.0.map(T::encode).into_iter().enumerate() {
+            if value != T::Packed::default() {
+                out[i / 8] |= 1 << (i % 8);
+                present.push(value.wrapping_sub(T::Packed::from_u128(1)));
+            }
+        }
+
+        let bit_width = T::Packed::bit_width(&present);
+        out.push(bit_width as u8);
+        out.extend(T::Packed::pack_values(&present, bit_width));
+
+        out
+    }
+
+    /// Reverses [`Self::pack_sparse`]. `input` must have the size given by [`Self::packed_len`].
+    fn unpack_sparse(input: &[u8]) -> Result<Self, Error> {
+        let (bitmap, input) = input.split_at(N.div_ceil(8));
+        let bit_width = input[0] as usize;
+
+        let indices = (0..N)
+            .filter(|&i| bitmap[i / 8] & (1 << (i % 8)) != 0)
+            .collect::<Vec<_>>();
+        let mut present = alloc::vec![T::Packed::default(); indices.len()];
+        T::Packed::unpack_values(bit_width, &input[1..], &mut present);
+
+        let mut values = [T::Packed::default(); N];
+        for (i, value) in indices.into_iter().zip(present) {
+            values[i] = value.wrapping_add(T::Packed::from_u128(1));
+        }
+
+        Self::decode_values(values, T::decode)
+    }
+}
+
+impl<T: ListElement, const N: usize> ReprPacked for List<T, N> {
+    /// Packs the list with the [`PackMode`] that gives the smallest output, preferring
+    /// [`PackMode::Plain`] on ties.
+    fn pack(&self) -> (usize, Vec<u8>) {
+        let (mode, _) = self
+            .packed_sizes()
+            .into_iter()
+            .min_by_key(|&(_, size)| size)
+            .unwrap_or((PackMode::Plain, 0));
+
+        self.pack_with(mode)
+    }
+
+    fn unpack(header: usize, input: &[u8]) -> Result<Self, Error> {
+        let expected_size = Self::packed_len(header, input)?;
+        if input.len() != expected_size {
+            return Err(Error::InvalidDataSize {
+                expected: expected_size,
+                got: input.len(),
+            });
+        }
+
+        match header {
+            DELTA_HEADER => Self::unpack_deltas(1, input),
+            DELTA_OF_DELTA_HEADER => Self::unpack_deltas(2, input),
+            BLOCKS_HEADER => Self::unpack_blocks(input),
+            SPARSE_HEADER => Self::unpack_sparse(input),
+            bit_width => {
+                let mut values = [T::Packed::default(); N];
+                T::Packed::unpack_values(bit_width, input, &mut values);
+                Self::decode_values(values, T::decode)
+            }
+        }
+    }
+}
+
+#[cfg(any(test, feature = "proptest"))]
+impl<T, const N: usize> proptest::arbitrary::Arbitrary for List<T, N>
+where
+    T: ListElement + proptest::arbitrary::Arbitrary + 'static,
+{
+    type Parameters = ();
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        use proptest::prelude::*;
+        prop::collection::vec(any::<T>(), N)
+            .prop_map(|v| Self(v.try_into().unwrap()))
+            .boxed()
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Display for List<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+impl<T, const N: usize> Deref for List<T, N> {
+    type Target = [T; N];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, const N: usize> DerefMut for List<T, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T, I: SliceIndex<[T]>, const N: usize> Index<I> for List<T, N> {
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &Self::Output {
+        &self.0[index]
+    }
+}
+
+impl<T, I: SliceIndex<[T]>, const N: usize> IndexMut<I> for List<T, N> {
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        &mut self.0[index]
+    }
+}
+
+impl<T, const N: usize> AsRef<[T]> for List<T, N> {
+    fn as_ref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl<T, const N: usize> AsMut<[T]> for List<T, N> {
+    fn as_mut(&mut self) -> &mut [T] {
+        &mut self.0
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for List<T, N> {
+    fn from(values: [T; N]) -> Self {
+        Self(values)
+    }
+}
+
+impl<T, const N: usize> From<List<T, N>> for [T; N] {
+    fn from(list: List<T, N>) -> Self {
+        list.0
+    }
+}
+
+impl<T: ListElement, const N: usize> TryFrom<&[T]> for List<T, N> {
+    type Error = Error;
+
+    fn try_from(values: &[T]) -> Result<Self, Error> {
+        Self::try_from_iter(values.iter().copied())
+    }
+}
+
+impl<T: ListElement, const N: usize> FromIterator<T> for List<T, N> {
+    /// Collects exactly `N` values into a list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator does not yield exactly `N` values. Use [`List::try_from_iter`] to
+    /// handle that case instead.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::try_from_iter(iter).expect("iterator length does not match the list length")
+    }
+}
+
+impl<T, const N: usize> IntoIterator for List<T, N> {
+    type Item = T;
+    type IntoIter = core::array::IntoIter<T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a List<T, N> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut List<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter_mut()
+    }
+}
+
+impl<T: ListElement, const N: usize> Default for List<T, N> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl<T: ListElement, const N: usize> ReprSize for List<T, N> {
+    const SIZE: usize = size_of::<T>() * N + 1;
+}
+
+impl<T: ListElement, const N: usize> ReprBytes<{ size_of::<T>() * N + 1 }> for List<T, N> {
+    fn from_bytes(input: [u8; size_of::<T>() * N + 1]) -> Self {
+        Self::try_from_bytes(input).expect("invalid packed list")
+    }
+
+    fn try_from_bytes(input: [u8; size_of::<T>() * N + 1]) -> Result<Self, Error> {
+        // First byte contains the header, either the bit width or the packing mode
+        let header = input[0] as usize;
+        let data = &input[1..];
+
+        // Extract the packed data
+        let data_size = Self::packed_len(header, data)?;
+        if data_size > data.len() {
+            return Err(Error::InvalidDataSize {
+                expected: data_size,
+                got: data.len(),
+            });
+        }
+
+        Self::unpack(header, &data[..data_size])
+    }
+
+    fn as_bytes(&self) -> [u8; size_of::<T>() * N + 1] {
+        let mut out = [0u8; size_of::<T>() * N + 1];
+
+        // Every list fits with the plain mode, so there is no need to search for a smaller one
+        let (header, packed) = self.pack_with(PackMode::Plain);
+        let data_size = packed.len();
+
+        // Store header in first byte
+        out[0] = header as u8;
+
+        // Copy packed data
+        out[1..1 + data_size].copy_from_slice(&packed);
+
+        // Only add padding and sentinel if there's room
+        let total = out.len();
+        if 1 + data_size < total - 1 {
+            // Fill remaining bytes with zeros
+            out[1 + data_size..total - 1].fill(0);
+            // Set last byte to 0xFF as sentinel
+            out[total - 1] = 0xFF;
+        }
+
+        out
+    }
+}
+
+/// A sorted set of up to `N` distinct `u64` values, encoded with Elias–Fano.
+///
+/// Each value is split into its lowest `low_bits` bits, which are packed as they are, and the
+/// remaining high bits, which are stored as a unary-coded bitmap. This takes about
+/// `2 + log2(max / len)` bits per value, and the encoding can be queried with [`EliasFano`]
+/// without decoding the whole set.
+///
+/// The packed data is laid out as `[len][low bits][high bits]`, with `len` as a little-endian
+/// `u32`, and the header returned by [`ReprPacked::pack`] is `low_bits`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SetU64<const N: usize> {
+    len: usize,
+    values: [u64; N],
+}
+
+impl<const N: usize> SetU64<N> {
+    /// The largest size of a set encoded with [`ReprPacked::to_compact`], for sizing buffers.
+    pub const MAX_COMPACT_SIZE: usize = 1 + varint_len(Self::SIZE - 1) + Self::SIZE - 1;
+
+    pub fn zero() -> Self {
+        Self {
+            len: 0,
+            values: [0; N],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the values in the set, in ascending order.
+    pub fn as_slice(&self) -> &[u64] {
+        &self.values[..self.len]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        self.as_slice().iter().copied()
+    }
+
+    pub fn contains(&self, value: u64) -> bool {
+        self.as_slice().binary_search(&value).is_ok()
+    }
+
+    /// Adds a value to the set, returning whether it was not present yet.
+    pub fn insert(&mut self, value: u64) -> Result<bool, Error> {
+        match self.as_slice().binary_search(&value) {
+            Ok(_) => Ok(false),
+            Err(_) if self.len == N => Err(Error::CapacityExceeded(N)),
+            Err(index) => {
+                self.values.copy_within(index..self.len, index + 1);
+                self.values[index] = value;
+                self.len += 1;
+                Ok(true)
             }
         }
+    }
 
-        #[allow(dead_code)]
-        impl<const N: usize> $list_type<N>
-        where
-            $type: SimdElement,
-            $lanes: SupportedLaneCount,
-        {
-            pub fn zero() -> Self {
-                Self([0; N])
+    /// Removes a value from the set, returning whether it was present.
+    pub fn remove(&mut self, value: u64) -> bool {
+        match self.as_slice().binary_search(&value) {
+            Ok(index) => {
+                self.values.copy_within(index + 1..self.len, index);
+                self.len -= 1;
+                self.values[self.len] = 0;
+                true
             }
+            Err(_) => false,
+        }
+    }
 
-            #[cfg(feature = "rand")]
-            pub fn random<R: rand::prelude::Rng>(rng: &mut R) -> Self {
-                let mut out = [0; N];
+    /// Returns a view over a set encoded with [`ReprBytes::as_bytes`], which can be queried
+    /// without decoding it.
+    pub fn view(input: &[u8]) -> Result<EliasFano<'_>, Error> {
+        match input.split_first() {
+            Some((&low_bits, input)) => EliasFano::new(low_bits as usize, input),
+            None => Err(Error::InvalidDataSize {
+                expected: Self::SIZE,
+                got: 0,
+            }),
+        }
+    }
 
-                for i in 0..N {
-                    out[i] = rng.gen::<$type>();
-                }
+    /// Returns the number of low bits per value that minimizes the size of the encoding.
+    fn low_bits(&self) -> usize {
+        match self.as_slice().last() {
+            Some(&max) => {
+                let universe = max as u128 + 1;
+                let len = self.len as u128;
 
-                Self(out)
+                if universe > len {
+                    (universe / len).ilog2() as usize
+                } else {
+                    0
+                }
             }
+            None => 0,
         }
+    }
+}
 
-        impl<const N: usize> ReprPacked for $list_type<N>
-        where
-            $type: SimdElement,
-            $lanes: SupportedLaneCount,
-            Self: ReprBytes<{ size_of::<$type>() * N + 1 }>,
-        {
-            fn pack(&self) -> (usize, Vec<u8>) {
-                // Find maximum value to determine required bits
-                let max_val = self.0.iter().copied().max().unwrap_or(0);
-                let bit_width = if max_val == 0 {
-                    0
-                } else {
-                    (core::mem::size_of::<$type>() * 8) - max_val.leading_zeros() as usize
-                };
-
-                // Calculate packed size in bytes (rounding up)
-                let byte_size = (N * bit_width + 7) / 8;
-                let mut out = Vec::with_capacity(byte_size);
-                let mask = if bit_width < (<$type>::BITS as usize) {
-                    ((1 as $type) << bit_width) - 1
-                } else {
-                    <$type>::MAX
-                };
+impl<const N: usize> TryFrom<&[u64]> for SetU64<N> {
+    type Error = Error;
 
-                // Pack values using determined bit width
-                for &item in self.0.iter() {
-                    let bytes = (item & mask).to_le_bytes();
-                    out.extend_from_slice(&bytes[..((bit_width + 7) / 8)]);
-                }
+    fn try_from(values: &[u64]) -> Result<Self, Error> {
+        let mut set = Self::zero();
 
-                // Trim any excess capacity
-                out.truncate(byte_size);
+        for &value in values {
+            set.insert(value)?;
+        }
 
-                (bit_width, out)
-            }
+        Ok(set)
+    }
+}
 
-            fn unpack(bit_width: usize, input: &[u8]) -> Result<Self, Error> {
-                // Special case: if bit_width is 0, all values are 0
-                if bit_width == 0 {
-                    return Ok(Self([0; N]));
-                }
+impl<const N: usize> ReprPacked for SetU64<N> {
+    fn pack(&self) -> (usize, Vec<u8>) {
+        let low_bits = self.low_bits();
+        let values = self.as_slice();
 
-                let expected_size = (N * bit_width + 7) / 8;
-                if input.len() != expected_size {
-                    return Err(Error::InvalidDataSize {
-                        expected: expected_size,
-                        got: input.len(),
-                    });
-                }
+        let mut out = (self.len as u32).to_le_bytes().to_vec();
 
-                let mut out = [0; N];
-                let mask = if bit_width < (<$type>::BITS as usize) {
-                    ((1 as $type) << bit_width) - 1
-                } else {
-                    <$type>::MAX
-                };
+        let lows = values
+            .iter()
+            .map(|&value| value & low_mask(low_bits))
+            .collect::<Vec<_>>();
+        out.extend(<u64 as PackedInt>::pack_values(&lows, low_bits));
 
-                let bytes_per_value = (bit_width + 7) / 8;
-                for (i, chunk) in input.chunks(bytes_per_value).enumerate() {
-                    if i >= N {
-                        break;
-                    }
+        // Value `i` sets bit `(value >> low_bits) + i`, so the bits set before it count the values
+        // and the bits unset count the high part
+        let high_bits = values
+            .last()
+            .map_or(0, |&max| high_part(max, low_bits) as usize + values.len());
+        let mut high = alloc::vec![0u8; high_bits.div_ceil(8)];
+        for (i, &value) in values.iter().enumerate() {
+            let position = high_part(value, low_bits) as usize + i;
+            high[position / 8] |= 1 << (position % 8);
+        }
+        out.extend(high);
 
-                    let mut bytes = [0u8; size_of::<$type>()];
-                    bytes[..chunk.len()].copy_from_slice(chunk);
-                    let value = <$type>::from_le_bytes(bytes);
+        (low_bits, out)
+    }
 
-                    out[i] = value & mask;
-                }
+    fn unpack(low_bits: usize, input: &[u8]) -> Result<Self, Error> {
+        let view = EliasFano::new(low_bits, input)?;
+        if view.len() > N {
+            return Err(Error::CapacityExceeded(N));
+        }
 
-                Ok(Self(out))
-            }
+        let expected_size = view.packed_len().ok_or(Error::InvalidSet)?;
+        if input.len() != expected_size {
+            return Err(Error::InvalidDataSize {
+                expected: expected_size,
+                got: input.len(),
+            });
         }
 
-        impl<const N: usize> fmt::Debug for $list_type<N> {
-            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-                write!(f, "List<{N}>")
+        let mut set = Self::zero();
+        for value in view.iter() {
+            if set.as_slice().last().is_some_and(|&last| last >= value) {
+                return Err(Error::InvalidSet);
             }
+
+            set.values[set.len] = value;
+            set.len += 1;
         }
 
-        #[cfg(any(test, feature = "proptest"))]
-        impl<const N: usize> proptest::arbitrary::Arbitrary for $list_type<N> {
-            type Parameters = ();
-            type Strategy = proptest::strategy::BoxedStrategy<Self>;
+        Ok(set)
+    }
+}
 
-            fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
-                use proptest::prelude::*;
-                prop::collection::vec(any::<$type>(), N)
-                    .prop_map(|v| Self(v.try_into().unwrap()))
-                    .boxed()
-            }
+impl<const N: usize> Default for SetU64<N> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl<const N: usize> fmt::Debug for SetU64<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(any(test, feature = "proptest"))]
+impl<const N: usize> proptest::arbitrary::Arbitrary for SetU64<N> {
+    type Parameters = ();
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        use proptest::prelude::*;
+        prop::collection::btree_set(any::<u64>(), 0..=N)
+            .prop_map(|values| {
+                Self::try_from(values.into_iter().collect::<Vec<_>>().as_slice()).unwrap()
+            })
+            .boxed()
+    }
+}
+
+// In the worst case every value takes 64 low bits and 3 high bits, since the number of low bits
+// keeps the high part of every value under 2 * len.
+impl<const N: usize> ReprSize for SetU64<N> {
+    const SIZE: usize = 5 + 8 * N + (3 * N).div_ceil(8);
+}
+
+impl<const N: usize> ReprBytes<{ 5 + 8 * N + (3 * N).div_ceil(8) }> for SetU64<N> {
+    fn from_bytes(input: [u8; 5 + 8 * N + (3 * N).div_ceil(8)]) -> Self {
+        Self::try_from_bytes(input).expect("invalid Elias-Fano set")
+    }
+
+    fn try_from_bytes(input: [u8; 5 + 8 * N + (3 * N).div_ceil(8)]) -> Result<Self, Error> {
+        let view = Self::view(&input)?;
+        let data_size = view.packed_len().ok_or(Error::InvalidSet)?;
+
+        Self::unpack(input[0] as usize, &input[1..1 + data_size])
+    }
+
+    fn as_bytes(&self) -> [u8; 5 + 8 * N + (3 * N).div_ceil(8)] {
+        let mut out = [0u8; 5 + 8 * N + (3 * N).div_ceil(8)];
+
+        let (low_bits, packed) = self.pack();
+        out[0] = low_bits as u8;
+        out[1..1 + packed.len()].copy_from_slice(&packed);
+
+        out
+    }
+}
+
+/// A read-only view over a [`SetU64`] packed with Elias–Fano, answering queries without decoding
+/// the whole set.
+#[derive(Clone, Copy, Debug)]
+pub struct EliasFano<'a> {
+    len: usize,
+    low_bits: usize,
+    low: &'a [u8],
+    high: &'a [u8],
+}
+
+impl<'a> EliasFano<'a> {
+    /// Reads a set packed by [`ReprPacked::pack`], given the `low_bits` header it returned.
+    ///
+    /// Only the sizes are checked, so querying a malformed set returns meaningless results
+    /// instead of an error. Use [`ReprPacked::unpack`] to validate the whole set.
+    pub fn new(low_bits: usize, input: &'a [u8]) -> Result<Self, Error> {
+        if low_bits > u64::BITS as usize {
+            return Err(Error::InvalidBitWidth(low_bits));
         }
 
-        impl<const N: usize> fmt::Display for $list_type<N> {
-            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-                write!(f, "{:?}", self.0)
-            }
+        if input.len() < 4 {
+            return Err(Error::InvalidDataSize {
+                expected: 4,
+                got: input.len(),
+            });
         }
 
-        impl<const N: usize> Deref for $list_type<N> {
-            type Target = [$type; N];
+        let (len, input) = input.split_at(4);
+        let len = u32::from_le_bytes(len.try_into()?) as usize;
+        let low_size = len
+            .checked_mul(low_bits)
+            .ok_or(Error::InvalidSet)?
+            .div_ceil(8);
 
-            fn deref(&self) -> &Self::Target {
-                &self.0
-            }
+        if input.len() < low_size {
+            return Err(Error::InvalidDataSize {
+                expected: 4 + low_size,
+                got: 4 + input.len(),
+            });
         }
 
-        impl<const N: usize> AsRef<[$type]> for $list_type<N> {
-            fn as_ref(&self) -> &[$type] {
-                &self.0
-            }
+        let (low, high) = input.split_at(low_size);
+
+        Ok(Self {
+            len,
+            low_bits,
+            low,
+            high,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the value at `index` in ascending order.
+    pub fn select(&self, index: usize) -> Option<u64> {
+        if index >= self.len {
+            return None;
         }
 
-        impl<const N: usize> Default for $list_type<N> {
-            fn default() -> Self {
-                Self::zero()
+        let position = select_bit(self.high, index, true)?;
+        Some(self.value(index, position))
+    }
+
+    /// Returns the number of values smaller than `value`.
+    pub fn rank(&self, value: u64) -> usize {
+        let bucket = high_part(value, self.low_bits);
+
+        // Values with a smaller high part all come before the `bucket`-th unset bit
+        let mut position = if bucket == 0 {
+            0
+        } else {
+            match usize::try_from(bucket - 1)
+                .ok()
+                .and_then(|bucket| select_bit(self.high, bucket, false))
+            {
+                Some(position) => position + 1,
+                None => return self.len,
             }
+        };
+        let mut index = position - bucket as usize;
+
+        // Then compare the values with the same high part
+        while index < self.len && get_bit(self.high, position) && self.value(index, position) < value
+        {
+            index += 1;
+            position += 1;
         }
 
-        impl<const N: usize> ReprBytes<{ size_of::<$type>() * N + 1 }> for $list_type<N> {
-            fn from_bytes(input: [u8; { size_of::<$type>() * N + 1 }]) -> Self {
-                // First byte contains the bit width
-                let bit_width = input[0] as usize;
-                let data_size = (N * bit_width + 7) / 8;
+        index
+    }
 
-                // Extract the packed data
-                let packed = &input[1..1 + data_size];
+    pub fn contains(&self, value: u64) -> bool {
+        self.select(self.rank(value)) == Some(value)
+    }
 
-                // Unpack using the stored bit width
-                Self::unpack(bit_width, packed).unwrap()
-            }
+    /// Returns the values in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        (0..self.high.len() * 8)
+            .filter(|&position| get_bit(self.high, position))
+            .take(self.len)
+            .enumerate()
+            .map(|(index, position)| self.value(index, position))
+    }
+
+    /// Returns the value at `index`, given the position of its bit in the high bits.
+    fn value(&self, index: usize, position: usize) -> u64 {
+        let high = ((position - index) as u64)
+            .checked_shl(self.low_bits as u32)
+            .unwrap_or(0);
+        let mut low = BitReader::new(self.low);
+        let low = low
+            .skip(index * self.low_bits)
+            .and_then(|_| low.read(self.low_bits))
+            .expect("low bits hold every value");
+
+        high | low
+    }
+
+    /// Returns the size of the packed set, or `None` if the high bits are missing values.
+    fn packed_len(&self) -> Option<usize> {
+        let high_size = match self.len {
+            0 => 0,
+            len => select_bit(self.high, len - 1, true)? / 8 + 1,
+        };
 
-            fn as_bytes(&self) -> [u8; { size_of::<$type>() * N + 1 }] {
-                let mut out = [0u8; { size_of::<$type>() * N + 1 }];
+        Some(4 + self.low.len() + high_size)
+    }
+}
 
-                // Pack the data and get bit width
-                let (bit_width, packed) = self.pack();
-                let data_size = (N * bit_width + 7) / 8;
+#[inline(always)]
+fn low_mask(bits: usize) -> u64 {
+    u64::MAX.checked_shr(u64::BITS - bits as u32).unwrap_or(0)
+}
 
-                // Store bit width in first byte
-                out[0] = bit_width as u8;
+#[inline(always)]
+fn high_part(value: u64, low_bits: usize) -> u64 {
+    value.checked_shr(low_bits as u32).unwrap_or(0)
+}
 
-                // Copy packed data
-                out[1..1 + data_size].copy_from_slice(&packed);
+#[inline(always)]
+fn get_bit(input: &[u8], position: usize) -> bool {
+    input
+        .get(position / 8)
+        .is_some_and(|byte| byte & (1 << (position % 8)) != 0)
+}
 
-                // Only add padding and sentinel if there's room
-                let total = out.len();
-                if 1 + data_size < total - 1 {
-                    // Fill remaining bytes with zeros
-                    out[1 + data_size..total - 1].fill(0);
-                    // Set last byte to 0xFF as sentinel
-                    out[total - 1] = 0xFF;
-                }
+/// Returns the position of the bit with the given rank among the bits equal to `bit`.
+fn select_bit(input: &[u8], mut rank: usize, bit: bool) -> Option<usize> {
+    for (i, &byte) in input.iter().enumerate() {
+        let byte = if bit { byte } else { !byte };
+        let count = byte.count_ones() as usize;
 
-                out
+        if rank < count {
+            let mut byte = byte;
+            for _ in 0..rank {
+                byte &= byte - 1;
             }
+
+            return Some(i * 8 + byte.trailing_zeros() as usize);
         }
-    };
-}
 
-impl_list!(u16, ListU16, u16x4, LaneCount<4>);
-impl_list!(u32, ListU32, u32x4, LaneCount<4>);
-impl_list!(u64, ListU64, u64x2, LaneCount<2>);
+        rank -= count;
+    }
+
+    None
+}
 
 #[cfg(test)]
 mod tests {
@@ -215,13 +1462,26 @@ mod tests {
 
     macro_rules! generate_tests {
         ($list_type:ident, $type:ty, $($size:expr),+) => {
+            generate_tests!($list_type, $type as $type, $($size),+);
+        };
+        ($list_type:ident, $signed:ty as $type:ty, $($size:expr),+) => {
             paste::paste! {
                 mod [<$list_type:snake _tests>] {
                     use super::*;
 
                     fn list<const N: usize>(max_value: $type) -> impl Strategy<Value = $list_type<N>> {
                         prop::collection::vec(0..=max_value, N)
-                            .prop_map(|v| $list_type(v.try_into().unwrap()))
+                            .prop_map(|v| List(v.into_iter().map(|value| <$signed>::decode(value).unwrap()).collect::<Vec<_>>().try_into().unwrap()))
+                    }
+
+                    // Lists whose largest value needs exactly `width` bits.
+                    fn list_with_width<const N: usize>(width: usize) -> impl Strategy<Value = $list_type<N>> {
+                        let max_value = <$type>::MAX.checked_shr(<$type>::BITS - width as u32).unwrap_or(0);
+
+                        (list::<N>(max_value), 0..N).prop_map(move |(mut list, i)| {
+                            list.0[i] = <$signed>::decode(max_value).unwrap();
+                            list
+                        })
                     }
 
                     $(
@@ -247,9 +1507,145 @@ mod tests {
                             #[strategy(list::<$size>(((1u128 << 16) - 1).min(<$type>::MAX.into()) as $type))]
                             input: $list_type<$size>
                         ) {
-                            let (bit_width, packed) = input.pack();
-                            let expected_packed_size = ($size * bit_width + 7) / 8;
+                            let (bit_width, packed) = input.pack_with(PackMode::Plain);
+                            let expected_packed_size = ($size * bit_width).div_ceil(8);
                             prop_assert_eq!(packed.len(), expected_packed_size);
+                            prop_assert!(input.pack().1.len() <= packed.len());
+                        }
+
+                        #[test_strategy::proptest]
+                        fn [<test_pack_width_ $size>](
+                            #[strategy(0..=<$type>::BITS as usize)]
+                            width: usize,
+                            #[strategy(list_with_width::<$size>(#width))]
+                            input: $list_type<$size>,
+                        ) {
+                            let (bit_width, packed) = input.pack_with(PackMode::Plain);
+
+                            prop_assert_eq!(bit_width, width);
+                            prop_assert_eq!(packed.len(), ($size * width).div_ceil(8));
+                            prop_assert_eq!($list_type::<$size>::unpack(bit_width, &packed)?, input);
+
+                            let (header, packed) = input.pack();
+                            prop_assert_eq!($list_type::<$size>::unpack_strict(header, &packed)?, input);
+                            prop_assert_eq!(<$list_type<$size>>::from_bytes(input.as_bytes()), input);
+                        }
+
+                        #[test_strategy::proptest]
+                        fn [<test_pack_simd_scalar_ $size>](
+                            #[strategy(0..=<$type>::BITS as usize)]
+                            width: usize,
+                            #[strategy(list_with_width::<$size>(#width))]
+                            input: $list_type<$size>,
+                        ) {
+                            let values = input.0.map(ListElement::encode);
+                            prop_assert_eq!(<$type as PackedInt>::bit_width(&values), width);
+
+                            let packed = <$type as PackedInt>::pack_values(&values, width);
+                            prop_assert_eq!(&packed, &pack_scalar(&values, width));
+
+                            let mut simd = [0; $size];
+                            let mut scalar = [0; $size];
+                            <$type as PackedInt>::unpack_values(width, &packed, &mut simd);
+                            unpack_scalar(width, &packed, &mut scalar);
+                            prop_assert_eq!(simd, values);
+                            prop_assert_eq!(scalar, values);
+                        }
+
+                        #[test_strategy::proptest]
+                        fn [<test_pack_modes_ $size>](
+                            #[strategy(list::<$size>(<$type>::MAX))]
+                            input: $list_type<$size>
+                        ) {
+                            for mode in [
+                                PackMode::Delta,
+                                PackMode::DeltaOfDelta,
+                                PackMode::Blocks,
+                                PackMode::Sparse,
+                            ] {
+                                let (header, packed) = input.pack_with(mode);
+                                prop_assert_eq!($list_type::<$size>::unpack(header, &packed)?, input);
+                            }
+
+                            for (mode, size) in input.packed_sizes() {
+                                prop_assert_eq!(input.pack_with(mode).1.len(), size);
+                            }
+                        }
+
+                        #[test_strategy::proptest]
+                        fn [<test_pack_sorted_ $size>](
+                            #[strategy(list::<$size>(<$type>::MAX))]
+                            input: $list_type<$size>
+                        ) {
+                            let mut sorted = input;
+                            sorted.0.sort();
+
+                            let (header, packed) = sorted.pack();
+                            prop_assert!(packed.len() <= sorted.pack_with(PackMode::Plain).1.len());
+                            prop_assert_eq!($list_type::<$size>::unpack_strict(header, &packed)?, sorted);
+                            prop_assert_eq!(<$list_type<$size>>::from_bytes_strict(sorted.as_bytes())?, sorted);
+                        }
+
+                        #[test]
+                        fn [<test_pack_sequence_ $size>]() {
+                            // Values growing at a steady rate have no second order differences
+                            let input = List::<$signed, $size>(core::array::from_fn(|i| (1000 + 3 * i) as _));
+                            let (header, packed) = input.pack_with(PackMode::DeltaOfDelta);
+
+                            assert_eq!(header, DELTA_OF_DELTA_HEADER);
+                            assert_eq!(packed[0], 0);
+                            assert_eq!(packed.len(), 1 + 2 * size_of::<$type>());
+                            assert!(input.pack().1.len() <= packed.len());
+                            assert_eq!($list_type::<$size>::from_bytes(input.as_bytes()), input);
+                        }
+
+                        #[test_strategy::proptest]
+                        fn [<test_pack_sparse_ $size>](
+                            #[strategy(list::<$size>(<$type>::MAX))]
+                            input: $list_type<$size>,
+                            #[strategy(0usize..$size)]
+                            index: usize,
+                        ) {
+                            let mut sparse = $list_type::<$size>::zero();
+                            sparse.0[index] = input.0[index];
+
+                            let (header, packed) = sparse.pack();
+                            prop_assert!(packed.len() <= sparse.pack_with(PackMode::Sparse).1.len());
+                            prop_assert_eq!($list_type::<$size>::unpack_strict(header, &packed)?, sparse);
+                            prop_assert_eq!(<$list_type<$size>>::from_bytes_strict(sparse.as_bytes())?, sparse);
+                        }
+
+                        #[test]
+                        fn [<test_invalid_header_ $size>]() {
+                            let mut bytes = $list_type::<$size>::zero().as_bytes();
+                            bytes[0] = 0xFE;
+                            assert!(matches!(
+                                $list_type::<$size>::try_from_bytes(bytes),
+                                Err(Error::InvalidBitWidth(0xFE))
+                            ));
+
+                            // A delta header with an invalid residue width
+                            bytes[0] = DELTA_HEADER as u8;
+                            bytes[1] = <$type>::BITS as u8 + 1;
+                            assert!($list_type::<$size>::try_from_bytes(bytes).is_err());
+
+                            // A block header table with an invalid block width
+                            bytes[0] = BLOCKS_HEADER as u8;
+                            assert!($list_type::<$size>::try_from_bytes(bytes).is_err());
+                        }
+
+                        #[test_strategy::proptest]
+                        fn [<test_compact_ $size>](
+                            #[strategy(list::<$size>(<$type>::MAX))]
+                            input: $list_type<$size>
+                        ) {
+                            let compact = input.to_compact();
+                            let (header, packed) = input.pack();
+
+                            prop_assert_eq!(compact[0] as usize, header);
+                            prop_assert_eq!(compact.len(), 1 + varint_len(packed.len()) + packed.len());
+                            prop_assert!(compact.len() <= $list_type::<$size>::MAX_COMPACT_SIZE);
+                            prop_assert_eq!($list_type::<$size>::from_compact_strict(&compact)?, input);
                         }
 
                         #[test_strategy::proptest]
@@ -260,10 +1656,69 @@ mod tests {
                             prop_assert_eq!(<$list_type<$size>>::from_bytes(input.as_bytes()), input);
                         }
 
+                        #[test_strategy::proptest]
+                        fn [<test_text_roundtrip_ $size>](
+                            #[strategy(list::<$size>(<$type>::MAX))]
+                            input: $list_type<$size>
+                        ) {
+                            prop_assert_eq!(input.to_hex(), hex::encode(input.as_bytes()));
+                            prop_assert_eq!(<$list_type<$size>>::from_hex(&input.to_hex())?, input);
+                            prop_assert_eq!(<$list_type<$size>>::from_base64(&input.to_base64())?, input);
+                        }
+
+                        #[test]
+                        fn [<test_invalid_bit_width_ $size>]() {
+                            let mut bytes = $list_type::<$size>::zero().as_bytes();
+                            bytes[0] = <$type>::BITS as u8 + 1;
+
+                            assert!(matches!(
+                                $list_type::<$size>::try_from_bytes(bytes),
+                                Err(Error::InvalidBitWidth(_))
+                            ));
+                            assert!($list_type::<$size>::from_slice(&bytes).is_err());
+                            assert!($list_type::<$size>::unpack(bytes[0] as usize, &[]).is_err());
+                        }
+
+                        #[test_strategy::proptest]
+                        fn [<test_strict_roundtrip_ $size>](
+                            #[strategy(list::<$size>(<$type>::MAX))]
+                            input: $list_type<$size>
+                        ) {
+                            let (bit_width, packed) = input.pack();
+                            prop_assert_eq!(<$list_type<$size>>::from_bytes_strict(input.as_bytes())?, input);
+                            prop_assert_eq!(<$list_type<$size>>::unpack_strict(bit_width, &packed)?, input);
+                        }
+
+                        #[test]
+                        fn [<test_strict_non_canonical_ $size>]() {
+                            let zero = $list_type::<$size>::zero();
+
+                            let mut bytes = zero.as_bytes();
+                            bytes[bytes.len() - 1] = 0;
+                            assert_eq!($list_type::<$size>::from_bytes(bytes), zero);
+                            assert!(matches!(
+                                $list_type::<$size>::from_bytes_strict(bytes),
+                                Err(Error::NonCanonical)
+                            ));
+
+                            let mut bytes = zero.as_bytes();
+                            bytes[1] = 1;
+                            assert!(matches!(
+                                $list_type::<$size>::from_slice_strict(&bytes),
+                                Err(Error::NonCanonical)
+                            ));
+
+                            let packed = [0u8; $size * size_of::<$type>()];
+                            assert!(matches!(
+                                $list_type::<$size>::unpack_strict(<$type>::BITS as usize, &packed),
+                                Err(Error::NonCanonical)
+                            ));
+                        }
+
                         #[test_strategy::proptest]
                         fn [<test_padding_ $size>](list: $list_type<$size>) {
                             let bytes = list.as_bytes();
-                            let (bit_width, packed) = list.pack();
+                            let (bit_width, packed) = list.pack_with(PackMode::Plain);
 
                             // Check that the first byte contains the correct bit width
                             prop_assert_eq!(bytes[0] as usize, bit_width);
@@ -284,7 +1739,363 @@ mod tests {
         };
     }
 
-    generate_tests!(ListU16, u16, 64, 128);
-    generate_tests!(ListU32, u32, 64, 128);
-    generate_tests!(ListU64, u64, 64, 128);
+    generate_tests!(ListU8, u8, 3, 7, 64, 128, 300);
+    generate_tests!(ListU16, u16, 3, 7, 64, 128, 300);
+    generate_tests!(ListU32, u32, 3, 7, 64, 128, 300);
+    generate_tests!(ListU64, u64, 3, 7, 64, 128, 300);
+    generate_tests!(ListU128, u128, 3, 7, 64, 128, 300);
+    generate_tests!(ListI8, i8 as u8, 3, 7, 64, 128, 300);
+    generate_tests!(ListI16, i16 as u16, 3, 7, 64, 128, 300);
+    generate_tests!(ListI32, i32 as u32, 3, 7, 64, 128, 300);
+    generate_tests!(ListI64, i64 as u64, 3, 7, 64, 128, 300);
+    generate_tests!(ListI128, i128 as u128, 3, 7, 64, 128, 300);
+
+    // Packs and unpacks any list, through the same code for every element type
+    fn roundtrip<T: ListElement, const N: usize>(list: List<T, N>) -> Result<List<T, N>, Error> {
+        let (header, packed) = list.pack();
+        List::unpack_strict(header, &packed)
+    }
+
+    #[test_strategy::proptest]
+    fn test_bool_roundtrip(input: List<bool, 300>) {
+        prop_assert_eq!(roundtrip(input)?, input);
+        prop_assert_eq!(
+            List::<bool, 300>::from_bytes_strict(input.as_bytes())?,
+            input
+        );
+
+        for mode in [
+            PackMode::Delta,
+            PackMode::DeltaOfDelta,
+            PackMode::Blocks,
+            PackMode::Sparse,
+        ] {
+            let (header, packed) = input.pack_with(mode);
+            prop_assert_eq!(List::<bool, 300>::unpack(header, &packed)?, input);
+        }
+    }
+
+    #[test]
+    fn test_bool_invalid() {
+        let flags = List([true, false, true, true]);
+        assert_eq!(flags.pack(), (1, vec![0b1101]));
+
+        // 2 is not a bool
+        assert!(matches!(
+            List::<bool, 4>::unpack(2, &[0b10]),
+            Err(Error::InvalidDiscriminant(2))
+        ));
+    }
+
+    #[test]
+    fn test_u128_wide_values() {
+        let input = List::<u128, 5>([u128::MAX, 0, 1 << 100, 3, u128::MAX >> 1]);
+        let (bit_width, packed) = input.pack_with(PackMode::Plain);
+
+        assert_eq!(bit_width, 128);
+        assert_eq!(packed.len(), 5 * 16);
+        assert_eq!(roundtrip(input).unwrap(), input);
+        assert_eq!(
+            List::<i128, 2>::from_bytes(List([i128::MIN, i128::MAX]).as_bytes()),
+            List([i128::MIN, i128::MAX])
+        );
+    }
+
+    #[test]
+    fn test_pack_blocks_outlier() {
+        let mut input = List::<u32, 1024>(core::array::from_fn(|i| (i % 16) as u32));
+        input.0[1000] = 1 << 31;
+
+        // Only the block with the outlier uses the full width
+        let (header, packed) = input.pack();
+        assert_eq!(header, BLOCKS_HEADER);
+        assert_eq!(packed.len(), 8 * 5 + 7 * 128 * 4 / 8 + 128 * 32 / 8);
+        assert_eq!(ListU32::unpack_strict(header, &packed).unwrap(), input);
+        assert_eq!(ListU32::from_bytes_strict(input.as_bytes()).unwrap(), input);
+
+        // The fixed-size encoding always uses the plain mode, but accepts the block table too
+        let bytes = input.as_bytes();
+        assert_eq!(bytes[0], 32);
+        assert_eq!(input.to_compact()[0] as usize, BLOCKS_HEADER);
+
+        let mut blocks = [0u8; 4 * 1024 + 1];
+        blocks[0] = BLOCKS_HEADER as u8;
+        blocks[1..1 + packed.len()].copy_from_slice(&packed);
+        assert_eq!(ListU32::<1024>::from_bytes(blocks), input);
+        assert!(matches!(
+            ListU32::<1024>::from_bytes_strict(blocks),
+            Err(Error::NonCanonical)
+        ));
+    }
+
+    #[test]
+    fn test_pack_sparse_counters() {
+        let mut input = ListU64::<256>::zero();
+        input.0[3] = 1 << 40;
+        input.0[200] = 7;
+
+        // 32 bytes of bitmap, the width and two values stored minus one in 40 bits
+        let (header, packed) = input.pack();
+        assert_eq!(header, SPARSE_HEADER);
+        assert_eq!(packed.len(), 32 + 1 + 10);
+        assert_eq!(ListU64::unpack_strict(header, &packed).unwrap(), input);
+
+        // Every list has a single accepted encoding
+        let (header, packed) = input.pack_with(PackMode::Plain);
+        assert_eq!(ListU64::<256>::unpack(header, &packed).unwrap(), input);
+        assert!(matches!(
+            ListU64::<256>::unpack_strict(header, &packed),
+            Err(Error::NonCanonical)
+        ));
+    }
+
+    #[test]
+    fn test_zigzag_bit_width() {
+        // -4 and 4 are encoded as 7 and 8
+        assert_eq!(List::<i32, 4>([-1, -2, -3, -4]).pack().0, 3);
+        assert_eq!(List::<i32, 4>([1, 2, 3, 4]).pack().0, 4);
+        assert_eq!(List::<i16, 8>([-1; 8]).pack(), (1, vec![0xFF]));
+
+        let extremes = List::<i64, 4>([i64::MIN, i64::MAX, 0, -1]);
+        let (bit_width, packed) = extremes.pack_with(PackMode::Plain);
+        assert_eq!(bit_width, 64);
+        assert_eq!(ListI64::unpack(bit_width, &packed).unwrap(), extremes);
+    }
+
+    macro_rules! generate_set_tests {
+        ($($size:literal),*) => {
+            paste::paste! {
+                $(
+                    #[test_strategy::proptest]
+                    fn [<test_set_ $size _roundtrip>](input: SetU64<$size>) {
+                        let (low_bits, packed) = input.pack();
+                        prop_assert_eq!(SetU64::<$size>::unpack_strict(low_bits, &packed)?, input);
+                        prop_assert_eq!(SetU64::<$size>::from_bytes_strict(input.as_bytes())?, input);
+
+                        let compact = input.to_compact();
+                        prop_assert!(compact.len() <= SetU64::<$size>::MAX_COMPACT_SIZE);
+                        prop_assert_eq!(SetU64::<$size>::from_compact_strict(&compact)?, input);
+                    }
+
+                    #[test_strategy::proptest]
+                    fn [<test_set_ $size _view>](input: SetU64<$size>, probe: u64) {
+                        let bytes = input.as_bytes();
+                        let view = SetU64::<$size>::view(&bytes)?;
+                        let values = input.as_slice();
+
+                        prop_assert_eq!(view.len(), values.len());
+                        prop_assert_eq!(view.iter().collect::<Vec<_>>(), values);
+
+                        for (i, &value) in values.iter().enumerate() {
+                            prop_assert_eq!(view.select(i), Some(value));
+                            prop_assert_eq!(view.rank(value), i);
+                            prop_assert!(view.contains(value));
+                        }
+
+                        prop_assert_eq!(view.select(values.len()), None);
+                        prop_assert_eq!(view.rank(probe), values.partition_point(|&v| v < probe));
+                        prop_assert_eq!(view.contains(probe), input.contains(probe));
+                    }
+
+                    #[test_strategy::proptest]
+                    fn [<test_set_ $size _insert_remove>](values: Vec<u64>) {
+                        let mut set = SetU64::<$size>::zero();
+                        let mut expected = alloc::collections::BTreeSet::new();
+
+                        for value in values {
+                            match set.insert(value) {
+                                Ok(inserted) => prop_assert_eq!(inserted, expected.insert(value)),
+                                Err(Error::CapacityExceeded(capacity)) => {
+                                    prop_assert_eq!(capacity, $size);
+                                    prop_assert_eq!(expected.len(), $size);
+                                    prop_assert!(!expected.contains(&value));
+                                }
+                                Err(e) => return Err(TestCaseError::fail(format!("{e}"))),
+                            }
+                        }
+                        prop_assert!(set.iter().eq(expected.iter().copied()));
+
+                        for value in expected.clone() {
+                            prop_assert!(set.remove(value));
+                            prop_assert!(!set.remove(value));
+                        }
+                        prop_assert_eq!(set, SetU64::zero());
+                    }
+                )*
+            }
+        };
+    }
+
+    generate_set_tests!(1, 7, 64, 300);
+
+    #[test]
+    fn test_set_compression() {
+        let values = (0..1000).map(|i| i * 100 + i % 7).collect::<Vec<u64>>();
+        let set = SetU64::<1000>::try_from(values.as_slice()).unwrap();
+
+        // 6 low bits and under 3 high bits per value, instead of 17 bits for a plain list
+        let (low_bits, packed) = set.pack();
+        assert_eq!(low_bits, 6);
+        assert_eq!(
+            packed.len(),
+            4 + 1000 * 6 / 8 + (1000 + (99_905usize >> 6)).div_ceil(8)
+        );
+
+        let bytes = set.as_bytes();
+        let view = SetU64::<1000>::view(&bytes).unwrap();
+        assert_eq!(view.select(500), Some(50_003));
+        assert_eq!(view.rank(50_003), 500);
+        assert!(!view.contains(50_004));
+    }
+
+    #[test]
+    fn test_set_invalid() {
+        let set = SetU64::<4>::try_from([3, 9, 27].as_slice()).unwrap();
+        let (low_bits, packed) = set.pack();
+
+        // Too many values for the capacity
+        assert!(matches!(
+            SetU64::<2>::unpack(low_bits, &packed),
+            Err(Error::CapacityExceeded(2))
+        ));
+
+        // Trailing data after the high bits
+        let mut trailing = packed.clone();
+        trailing.push(0);
+        assert!(matches!(
+            SetU64::<4>::unpack(low_bits, &trailing),
+            Err(Error::InvalidDataSize { .. })
+        ));
+
+        // Missing values in the high bits
+        let mut missing = packed.clone();
+        *missing.last_mut().unwrap() = 0;
+        assert!(matches!(
+            SetU64::<4>::unpack(low_bits, &missing),
+            Err(Error::InvalidSet)
+        ));
+
+        // Repeated values
+        let mut repeated = (2u32).to_le_bytes().to_vec();
+        repeated.push(0b11);
+        assert!(matches!(
+            SetU64::<4>::unpack(0, &repeated),
+            Err(Error::InvalidSet)
+        ));
+
+        // Too many low bits
+        assert!(matches!(
+            SetU64::<4>::unpack(65, &packed),
+            Err(Error::InvalidBitWidth(65))
+        ));
+
+        // Decoding with another number of low bits is not canonical
+        let (_, packed) = SetU64::<4>::try_from([1, 2].as_slice()).unwrap().pack();
+        assert_eq!(packed, [2, 0, 0, 0, 0b1010]);
+        let mut other = (2u32).to_le_bytes().to_vec();
+        other.extend([0b01, 0b101]);
+        assert_eq!(SetU64::<4>::unpack(1, &other).unwrap().as_slice(), [1, 2]);
+        assert!(matches!(
+            SetU64::<4>::unpack_strict(1, &other),
+            Err(Error::NonCanonical)
+        ));
+    }
+
+    #[test]
+    fn test_construction() {
+        let list = List::from([1u16, 2, 3]);
+        assert_eq!(list, List::try_from([1u16, 2, 3].as_slice()).unwrap());
+        assert_eq!(list, (1..=3).collect());
+        assert_eq!(list.into_array(), [1, 2, 3]);
+
+        assert!(matches!(
+            ListU16::<3>::try_from([1, 2].as_slice()),
+            Err(Error::InvalidDataSize {
+                expected: 3,
+                got: 2
+            })
+        ));
+        assert!(matches!(
+            ListU16::<3>::try_from_iter(0..5),
+            Err(Error::InvalidDataSize {
+                expected: 3,
+                got: 5
+            })
+        ));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_collect_wrong_length() {
+        let _: ListU32<4> = (0..3).collect();
+    }
+
+    #[test_strategy::proptest]
+    fn test_mutation(input: ListI32<64>, #[strategy(0usize..64)] index: usize, value: i32) {
+        let mut list = input;
+        list[index] = value;
+        prop_assert_eq!(list[index], value);
+        prop_assert_eq!(&list[..index], &input[..index]);
+        prop_assert_eq!(&list[index + 1..], &input[index + 1..]);
+
+        for value in list.iter_mut() {
+            *value = value.wrapping_neg();
+        }
+        prop_assert_eq!(list.map(i32::wrapping_neg)[index], value);
+
+        let widened = input.map(i64::from);
+        prop_assert!(widened.iter().zip(&input).all(|(&a, &b)| a == b as i64));
+        prop_assert_eq!(ListI64::from_bytes(widened.as_bytes()), widened);
+    }
+
+    #[test]
+    fn test_ordering_and_debug() {
+        assert!(List([1u8, 2, 3]) < List([1, 3, 0]));
+        assert_eq!(List([1u8, 2]).max(List([1, 1])), List([1, 2]));
+        assert_eq!(format!("{:?}", List([-1i8, 2])), "List([-1, 2])");
+        assert_eq!(format!("{}", List([-1i8, 2])), "[-1, 2]");
+    }
+
+    #[test]
+    fn test_compact() {
+        // Scattered full-width values need every byte, plus the header and a two byte length
+        let full = ListU64::<64>::from(core::array::from_fn(|i| {
+            (i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1 << 63
+        }));
+        assert_eq!(full.to_compact().len(), ListU64::<64>::MAX_COMPACT_SIZE);
+        assert_eq!(ListU64::<64>::MAX_COMPACT_SIZE, 1 + 2 + 64 * 8);
+
+        // A zero list takes the header and a zero length
+        let zero = ListU32::<1000>::zero();
+        assert_eq!(zero.to_compact(), [0, 0]);
+        assert_eq!(ListU32::<1000>::from_compact_strict(&[0, 0]).unwrap(), zero);
+
+        // Redundant varint bytes are only accepted outside of strict decoding
+        let padded = [0, 0x80, 0x00];
+        assert_eq!(ListU32::<1000>::from_compact(&padded).unwrap(), zero);
+        assert!(matches!(
+            ListU32::<1000>::from_compact_strict(&padded),
+            Err(Error::NonCanonical)
+        ));
+
+        // Truncated and trailing input
+        let compact = List::from([1u16, 2, 3]).to_compact();
+        for len in 0..compact.len() {
+            assert!(ListU16::<3>::from_compact(&compact[..len]).is_err());
+        }
+        let mut trailing = compact.clone();
+        trailing.push(0);
+        assert!(matches!(
+            ListU16::<3>::from_compact(&trailing),
+            Err(Error::InvalidDataSize { .. })
+        ));
+
+        // Lengths that do not fit in a usize
+        assert!(matches!(
+            ListU16::<3>::from_compact(&[
+                0, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F
+            ]),
+            Err(Error::IntConversionError(_))
+        ));
+    }
 }
@@ -7,19 +7,67 @@ use core::fmt::Debug;
 
 use crate::Error;
 
-pub trait ReprBytes<const N: usize>: Sized + Debug + Default {
-    const SIZE: usize = N;
+/// The encoded size of a type, in bytes.
+///
+/// Every [`ReprBytes<N>`] implementation must also implement this trait with `SIZE == N`, which the
+/// provided methods of [`ReprBytes`] check at compile time. Unlike
+/// `N`, the size can be named from the type alone, which is what lets the derive macro compute the
+/// size of a struct from the sizes of its fields.
+pub trait ReprSize {
+    const SIZE: usize;
+}
 
+pub trait ReprBytes<const N: usize>: ReprSize + Sized + Debug + Default {
+    /// Decodes a value, panicking if `input` is not a valid encoding.
+    ///
+    /// Use [`ReprBytes::try_from_bytes`] for input that comes from an untrusted source.
     fn from_bytes(input: [u8; N]) -> Self;
+
+    /// Encodes a value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value has no encoding, like a bitfield holding a value wider than its
+    /// declared bits. Use [`ReprBytes::try_as_bytes`] for values that are not known to fit.
     fn as_bytes(&self) -> [u8; N];
 
+    /// Fails to compile when [`ReprSize::SIZE`] is not `N`. Every provided method evaluates it, so
+    /// a mismatched implementation is rejected as soon as any of them is used.
+    #[doc(hidden)]
+    const SIZE_MATCHES: () = assert!(
+        <Self as ReprSize>::SIZE == N,
+        "ReprSize::SIZE must be equal to the N of ReprBytes<N>"
+    );
+
+    /// Decodes a value, returning an error if `input` is not a valid encoding.
+    ///
+    /// Types for which every input is valid can rely on the default implementation, which
+    /// forwards to [`ReprBytes::from_bytes`].
+    #[inline(always)]
+    fn try_from_bytes(input: [u8; N]) -> Result<Self, Error> {
+        let () = Self::SIZE_MATCHES;
+        Ok(Self::from_bytes(input))
+    }
+
+    /// Encodes a value, returning an error if it has no encoding.
+    ///
+    /// Types for which every value can be encoded can rely on the default implementation, which
+    /// forwards to [`ReprBytes::as_bytes`].
+    #[inline(always)]
+    fn try_as_bytes(&self) -> Result<[u8; N], Error> {
+        let () = Self::SIZE_MATCHES;
+        Ok(self.as_bytes())
+    }
+
     #[inline(always)]
     fn zero() -> Self {
+        let () = Self::SIZE_MATCHES;
         Self::from_bytes([0u8; N])
     }
 
     #[inline]
     fn to_bytes(&self) -> Vec<u8> {
+        let () = Self::SIZE_MATCHES;
         self.as_bytes().to_vec()
     }
 
@@ -28,6 +76,8 @@ pub trait ReprBytes<const N: usize>: Sized + Debug + Default {
     where
         [(); N]:,
     {
+        let () = Self::SIZE_MATCHES;
+
         if input.len() != N {
             return Err(Error::InvalidDataSize {
                 expected: N,
@@ -35,15 +85,50 @@ pub trait ReprBytes<const N: usize>: Sized + Debug + Default {
             });
         }
 
-        Ok(Self::from_bytes(input.try_into()?))
+        Self::try_from_bytes(input.try_into()?)
+    }
+
+    /// Decodes a value, rejecting any `input` that is not its canonical encoding.
+    ///
+    /// Returns [`Error::NonCanonical`] if re-encoding the decoded value would not reproduce
+    /// `input` exactly, so every value has exactly one accepted encoding.
+    #[inline]
+    fn from_bytes_strict(input: [u8; N]) -> Result<Self, Error> {
+        let () = Self::SIZE_MATCHES;
+        let value = Self::try_from_bytes(input)?;
+
+        if value.as_bytes() != input {
+            return Err(Error::NonCanonical);
+        }
+
+        Ok(value)
+    }
+
+    #[inline]
+    fn from_slice_strict(input: &[u8]) -> Result<Self, Error>
+    where
+        [(); N]:,
+    {
+        let () = Self::SIZE_MATCHES;
+
+        if input.len() != N {
+            return Err(Error::InvalidDataSize {
+                expected: N,
+                got: input.len(),
+            });
+        }
+
+        Self::from_bytes_strict(input.try_into()?)
     }
 }
 
 macro_rules! impl_repr_num {
     ($type:ty) => {
-        impl ReprBytes<{ core::mem::size_of::<$type>() }> for $type {
+        impl ReprSize for $type {
             const SIZE: usize = core::mem::size_of::<$type>();
+        }
 
+        impl ReprBytes<{ core::mem::size_of::<$type>() }> for $type {
             #[inline(always)]
             fn from_bytes(input: [u8; core::mem::size_of::<$type>()]) -> Self {
                 <$type>::from_le_bytes(input)
@@ -55,6 +140,31 @@ macro_rules! impl_repr_num {
             }
         }
     };
+    // Pointer-sized integers are always encoded as `$repr`, so the encoding is the same on 32-bit
+    // and 64-bit targets. `from_bytes` truncates values that do not fit the target's `$type`, while
+    // `try_from_bytes` rejects them.
+    ($type:ty as $repr:ty) => {
+        impl ReprSize for $type {
+            const SIZE: usize = core::mem::size_of::<$repr>();
+        }
+
+        impl ReprBytes<{ core::mem::size_of::<$repr>() }> for $type {
+            #[inline(always)]
+            fn from_bytes(input: [u8; core::mem::size_of::<$repr>()]) -> Self {
+                <$repr>::from_le_bytes(input) as $type
+            }
+
+            #[inline(always)]
+            fn as_bytes(&self) -> [u8; core::mem::size_of::<$repr>()] {
+                (*self as $repr).to_le_bytes()
+            }
+
+            #[inline(always)]
+            fn try_from_bytes(input: [u8; core::mem::size_of::<$repr>()]) -> Result<Self, Error> {
+                Ok(<$type>::try_from(<$repr>::from_le_bytes(input))?)
+            }
+        }
+    };
 }
 
 impl_repr_num!(u8);
@@ -67,5 +177,5 @@ impl_repr_num!(i16);
 impl_repr_num!(i32);
 impl_repr_num!(i64);
 impl_repr_num!(i128);
-impl_repr_num!(usize);
-impl_repr_num!(isize);
+impl_repr_num!(usize as u64);
+impl_repr_num!(isize as i64);
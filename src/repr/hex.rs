@@ -1,56 +1,48 @@
 #![allow(incomplete_features)]
 
-use alloc::{string::String, vec::Vec};
+use alloc::string::String;
 
-use crate::{Error, ReprBytes};
+use crate::{
+    bytes::{decode_hex, encode_hex},
+    Error,
+    ReprBytes,
+};
 
 pub trait ReprHex<const N: usize>: Sized + ReprBytes<N> {
     const HEX_SIZE: usize = N * 2;
 
     fn to_hex(&self) -> String;
     fn from_hex(input: &str) -> Result<Self, Error>;
-}
-
-macro_rules! impl_repr_num {
-    ($type:ty) => {
-        impl ReprHex<{ core::mem::size_of::<$type>() }> for $type {
-            #[inline]
-            fn to_hex(&self) -> String {
-                const LOOKUP: [u8; 16] = *b"0123456789abcdef";
-                let bytes = self.as_bytes();
-                let mut result = Vec::with_capacity(Self::HEX_SIZE);
 
-                for byte in bytes {
-                    result.push(LOOKUP[(byte >> 4) as usize]);
-                    result.push(LOOKUP[(byte & 0xf) as usize]);
-                }
+    /// Decodes a value from hex, rejecting any `input` that is not its canonical encoding.
+    ///
+    /// See [`ReprBytes::from_bytes_strict`].
+    #[inline]
+    fn from_hex_strict(input: &str) -> Result<Self, Error> {
+        let value = Self::from_hex(input)?;
 
-                // Safe because we only used valid ASCII hex digits
-                unsafe { String::from_utf8_unchecked(result) }
-            }
+        if value.to_hex() != input {
+            return Err(Error::NonCanonical);
+        }
 
-            #[inline]
-            fn from_hex(input: &str) -> Result<Self, Error> {
-                if input.len() != Self::HEX_SIZE {
-                    return Err(Error::InvalidDataSize {
-                        expected: Self::HEX_SIZE,
-                        got: input.len(),
-                    });
-                }
+        Self::from_bytes_strict(value.as_bytes())
+    }
+}
 
-                let input = input.as_bytes();
-                let mut bytes = [0u8; { core::mem::size_of::<$type>() }];
+/// Every [`ReprBytes`] type is encoded as the hex of its bytes.
+impl<const N: usize, T: ReprBytes<N>> ReprHex<N> for T {
+    #[inline]
+    fn to_hex(&self) -> String {
+        encode_hex(&self.as_bytes())
+    }
 
-                for (i, chunk) in input.chunks_exact(2).enumerate() {
-                    let hi = from_hex_digit(chunk[0])?;
-                    let lo = from_hex_digit(chunk[1])?;
-                    bytes[i] = (hi << 4) | lo;
-                }
+    #[inline]
+    fn from_hex(input: &str) -> Result<Self, Error> {
+        let mut bytes = [0u8; N];
+        decode_hex(input, &mut bytes)?;
 
-                Ok(Self::from_bytes(bytes))
-            }
-        }
-    };
+        Self::try_from_bytes(bytes)
+    }
 }
 
 pub(crate) fn from_hex_digit(digit: u8) -> Result<u8, Error> {
@@ -60,16 +52,3 @@ pub(crate) fn from_hex_digit(digit: u8) -> Result<u8, Error> {
         _ => Err(Error::InvalidHexDigit(digit as char)),
     }
 }
-
-impl_repr_num!(u8);
-impl_repr_num!(u16);
-impl_repr_num!(u32);
-impl_repr_num!(u64);
-impl_repr_num!(u128);
-impl_repr_num!(i8);
-impl_repr_num!(i16);
-impl_repr_num!(i32);
-impl_repr_num!(i64);
-impl_repr_num!(i128);
-impl_repr_num!(usize);
-impl_repr_num!(isize);
@@ -4,5 +4,11 @@ mod hex;
 mod packed;
 
 pub(crate) use hex::from_hex_digit;
+pub(crate) use packed::varint_len;
 
-pub use self::{base64::ReprBase64, bytes::ReprBytes, hex::ReprHex, packed::ReprPacked};
+pub use self::{
+    base64::ReprBase64,
+    bytes::{ReprBytes, ReprSize},
+    hex::ReprHex,
+    packed::ReprPacked,
+};
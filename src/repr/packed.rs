@@ -3,6 +3,126 @@ use alloc::vec::Vec;
 use crate::Error;
 
 pub trait ReprPacked: Sized {
+    /// Packs the value, returning the header needed to unpack it along with the packed data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value has no packed form, like a field holding a value wider than its
+    /// declared bits. Use [`ReprPacked::try_pack`] for values that are not known to fit.
     fn pack(&self) -> (usize, Vec<u8>);
     fn unpack(bit_width: usize, input: &[u8]) -> Result<Self, Error>;
+
+    /// Packs the value, returning an error if it has no packed form.
+    ///
+    /// Types for which every value can be packed can rely on the default implementation, which
+    /// forwards to [`ReprPacked::pack`].
+    #[inline]
+    fn try_pack(&self) -> Result<(usize, Vec<u8>), Error> {
+        Ok(self.pack())
+    }
+
+    /// Unpacks a value, rejecting any input that [`ReprPacked::pack`] would not produce for it.
+    #[inline]
+    fn unpack_strict(bit_width: usize, input: &[u8]) -> Result<Self, Error> {
+        let value = Self::unpack(bit_width, input)?;
+        let (expected_width, expected) = value.pack();
+
+        if expected_width != bit_width || expected != input {
+            return Err(Error::NonCanonical);
+        }
+
+        Ok(value)
+    }
+
+    /// Encodes the value as `[header][payload length][payload]`, keeping only the bytes that
+    /// [`ReprPacked::pack`] produced.
+    ///
+    /// The header takes a single byte and the payload length is a LEB128 varint, so small values
+    /// take only a few bytes more than their packed payload.
+    fn to_compact(&self) -> Vec<u8> {
+        let (header, payload) = self.pack();
+
+        let mut out = Vec::with_capacity(1 + varint_len(payload.len()) + payload.len());
+        out.push(header as u8);
+        write_varint(&mut out, payload.len());
+        out.extend(payload);
+
+        out
+    }
+
+    /// Decodes a value encoded with [`ReprPacked::to_compact`]. `input` must hold exactly one
+    /// value.
+    fn from_compact(input: &[u8]) -> Result<Self, Error> {
+        let (&header, input) = input.split_first().ok_or(Error::InvalidDataSize {
+            expected: 1,
+            got: 0,
+        })?;
+        let (len, payload) = read_varint(input)?;
+
+        if payload.len() != len {
+            return Err(Error::InvalidDataSize {
+                expected: len,
+                got: payload.len(),
+            });
+        }
+
+        Self::unpack(header as usize, payload)
+    }
+
+    /// Decodes a value, rejecting any input that [`ReprPacked::to_compact`] would not produce for
+    /// it, including payload lengths with redundant varint bytes.
+    fn from_compact_strict(input: &[u8]) -> Result<Self, Error> {
+        let value = Self::from_compact(input)?;
+
+        if value.to_compact() != input {
+            return Err(Error::NonCanonical);
+        }
+
+        Ok(value)
+    }
+}
+
+/// Returns the number of bytes taken by `value` as a LEB128 varint.
+pub(crate) const fn varint_len(value: usize) -> usize {
+    let bits = usize::BITS - value.leading_zeros();
+
+    if bits == 0 {
+        1
+    } else {
+        bits.div_ceil(7) as usize
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+
+    out.push(value as u8);
+}
+
+/// Reads a LEB128 varint, returning it along with the rest of `input`.
+fn read_varint(input: &[u8]) -> Result<(usize, &[u8]), Error> {
+    let mut value = 0usize;
+
+    for (i, &byte) in input.iter().enumerate() {
+        let shift = 7 * i as u32;
+        let bits = (byte & 0x7F) as usize;
+
+        if shift >= usize::BITS || (bits << shift) >> shift != bits {
+            return Err(Error::IntConversionError("varint overflows usize".into()));
+        }
+
+        value |= bits << shift;
+
+        if byte & 0x80 == 0 {
+            return Ok((value, &input[i + 1..]));
+        }
+    }
+
+    Err(Error::InvalidDataSize {
+        expected: input.len() + 1,
+        got: input.len(),
+    })
 }
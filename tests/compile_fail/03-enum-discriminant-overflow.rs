@@ -0,0 +1,11 @@
+use mucodec::ReprBytes;
+
+#[derive(Debug, Default, ReprBytes)]
+pub enum Data {
+    #[default]
+    First,
+    #[mucodec(discriminant = 256)]
+    Second,
+}
+
+fn main() {}
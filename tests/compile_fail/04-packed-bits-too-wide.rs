@@ -0,0 +1,9 @@
+use mucodec::ReprPacked;
+
+#[derive(ReprPacked)]
+pub struct Wide {
+    #[mucodec(bits = 200)]
+    value: u128,
+}
+
+fn main() {}
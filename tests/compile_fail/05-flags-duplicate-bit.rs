@@ -0,0 +1,11 @@
+use mucodec::ReprFlags;
+
+#[derive(Clone, Copy, PartialEq, Eq, ReprFlags)]
+pub enum Permission {
+    Read,
+    Write,
+    #[mucodec(bit = 1)]
+    Admin,
+}
+
+fn main() {}
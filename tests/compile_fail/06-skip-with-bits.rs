@@ -0,0 +1,11 @@
+use mucodec::ReprBytes;
+
+#[derive(Debug, Default, ReprBytes)]
+pub struct Header {
+    #[mucodec(bits = 4)]
+    version: u8,
+    #[mucodec(skip, bits = 4)]
+    cached: u8,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use mucodec::ReprBytes;
+
+#[derive(Debug, Default, ReprBytes)]
+pub struct Header {
+    #[mucodec(bits = 4, endian = "big")]
+    version: u8,
+    #[mucodec(bits = 4)]
+    kind: u8,
+}
+
+#[derive(Debug, Default, ReprBytes)]
+#[mucodec(endian = "middle")]
+pub struct Body {
+    length: u32,
+}
+
+fn main() {}
@@ -0,0 +1,26 @@
+use mucodec::ReprBytes;
+
+// The C layout needs every field to be encoded with its in-memory size
+#[derive(Debug, Default, ReprBytes)]
+#[mucodec(layout = "c")]
+pub struct Header {
+    kind: u8,
+    #[mucodec(with = wide)]
+    value: u32,
+}
+
+mod wide {
+    use mucodec::Error;
+
+    pub const SIZE: usize = 8;
+
+    pub fn encode(value: &u32) -> [u8; SIZE] {
+        (*value as u64).to_le_bytes()
+    }
+
+    pub fn decode(input: [u8; SIZE]) -> Result<u32, Error> {
+        Ok(u64::from_le_bytes(input).try_into()?)
+    }
+}
+
+fn main() {}
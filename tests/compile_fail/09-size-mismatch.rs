@@ -0,0 +1,22 @@
+use mucodec::{ReprBytes, ReprSize};
+
+#[derive(Debug, Default)]
+pub struct Pair(u16);
+
+impl ReprSize for Pair {
+    const SIZE: usize = 4;
+}
+
+impl ReprBytes<2> for Pair {
+    fn from_bytes(input: [u8; 2]) -> Self {
+        Self(u16::from_le_bytes(input))
+    }
+
+    fn as_bytes(&self) -> [u8; 2] {
+        self.0.to_le_bytes()
+    }
+}
+
+fn main() {
+    let _ = Pair::zero();
+}
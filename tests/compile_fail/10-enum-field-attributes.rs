@@ -0,0 +1,13 @@
+use mucodec::ReprBytes;
+
+#[derive(Debug, Default, ReprBytes)]
+pub enum Message {
+    #[default]
+    Ping,
+    Data {
+        #[mucodec(endian = "big")]
+        length: u32,
+    },
+}
+
+fn main() {}
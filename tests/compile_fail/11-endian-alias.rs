@@ -0,0 +1,12 @@
+use mucodec::ReprBytes;
+
+type Amount = u64;
+
+#[derive(Debug, Default, ReprBytes)]
+#[mucodec(endian = "big")]
+pub struct Transfer {
+    source: u32,
+    amount: Amount,
+}
+
+fn main() {}
@@ -1,6 +1,6 @@
 use mucodec::{Bytes, ReprBytes};
 
-#[derive(Debug, ReprBytes)]
+#[derive(Debug, Default, ReprBytes)]
 pub struct Data {
     name: Bytes<32>,
 }
@@ -1,6 +1,6 @@
 use mucodec::{Bytes, ListU16, ListU32, ListU64, ReprBytes, String};
 
-#[derive(Debug, ReprBytes)]
+#[derive(Debug, Default, ReprBytes)]
 pub struct Data {
     bytes1: Bytes<1>,
     bytes2: Bytes<24>,
@@ -32,11 +32,11 @@ fn main() {
     assert_eq!(data.bytes1, Bytes::<1>::zero());
     assert_eq!(data.bytes2, Bytes::<24>::zero());
     assert_eq!(data.bytes3, Bytes::<256>::zero());
-    assert_eq!(data.bytes4, Bytes::<13>::zero());
-    assert_eq!(data.string1, String::<1>::zero());
+    assert_eq!(data.bytes4, Bytes::<768>::zero());
+    assert_eq!(data.string1, String::<8>::zero());
     assert_eq!(data.string2, String::<24>::zero());
     assert_eq!(data.string3, String::<256>::zero());
-    assert_eq!(data.string4, String::<767>::zero());
+    assert_eq!(data.string4, String::<768>::zero());
     assert_eq!(data.u8_field, 0);
     assert_eq!(data.u16_field, 0);
     assert_eq!(data.u32_field, 0);
@@ -1,18 +1,18 @@
 use mucodec::{Bytes, ReprBytes};
 
-#[derive(Debug, PartialEq, ReprBytes)]
+#[derive(Debug, Default, PartialEq, ReprBytes)]
 pub struct A(Bytes<64>);
 
-#[derive(Debug, PartialEq, ReprBytes)]
+#[derive(Debug, Default, PartialEq, ReprBytes)]
 pub struct B(A);
 
-#[derive(Debug, PartialEq, ReprBytes)]
+#[derive(Debug, Default, PartialEq, ReprBytes)]
 pub struct C {
     a: A,
     b: B,
 }
 
-#[derive(Debug, PartialEq, ReprBytes)]
+#[derive(Debug, Default, PartialEq, ReprBytes)]
 pub struct Data {
     a: A,
     b: B,
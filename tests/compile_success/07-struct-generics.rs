@@ -1,6 +1,6 @@
 use mucodec::ReprBytes;
 
-#[derive(Debug, ReprBytes)]
+#[derive(Debug, Default, ReprBytes)]
 pub struct Data<T: ReprBytes<4>> {
     inner: T,
 }
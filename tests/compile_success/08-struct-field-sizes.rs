@@ -0,0 +1,29 @@
+use mucodec::{Bytes, ListU16, ListU64, ReprBytes, ReprSize, String};
+
+#[derive(Debug, Default, PartialEq, ReprBytes)]
+pub struct Inner {
+    name: String<24>,
+    values: ListU16<4>,
+}
+
+#[derive(Debug, Default, PartialEq, ReprBytes)]
+pub struct Outer<T: ReprBytes<8>> {
+    id: Bytes<32>,
+    inner: Inner,
+    offsets: ListU64<2>,
+    index: usize,
+    generic: T,
+}
+
+fn main() {
+    assert_eq!(Inner::SIZE, 24 + (4 * 2 + 1));
+    assert_eq!(Outer::<u64>::SIZE, 32 + Inner::SIZE + (2 * 8 + 1) + 8 + 8);
+
+    let data = Outer::<u64> {
+        index: 42,
+        generic: 7,
+        ..Default::default()
+    };
+    let bytes: [u8; 98] = data.as_bytes();
+    assert_eq!(Outer::<u64>::from_bytes(bytes), data);
+}
@@ -0,0 +1,64 @@
+use mucodec::{Bytes, Error, ReprBytes, ReprSize};
+
+#[derive(Debug, Default, PartialEq, ReprBytes)]
+pub enum Message {
+    #[default]
+    Ping,
+    Transfer {
+        to: Bytes<32>,
+        amount: u64,
+    },
+    Memo(u32, Bytes<4>),
+}
+
+#[derive(Debug, Default, PartialEq, ReprBytes)]
+#[mucodec(tag = u16)]
+pub enum Tagged {
+    #[default]
+    #[mucodec(discriminant = 0x100)]
+    First,
+    Second(u8),
+    #[mucodec(discriminant = 7)]
+    Third { value: u16 },
+}
+
+#[derive(Debug, Default, PartialEq, ReprBytes)]
+pub struct Wrapper {
+    id: u8,
+    tagged: Tagged,
+}
+
+fn main() {
+    assert_eq!(Message::SIZE, 1 + 32 + 8);
+    assert_eq!(Message::zero(), Message::Ping);
+
+    let transfer = Message::Transfer {
+        to: Bytes::from_bytes([1u8; 32]),
+        amount: 42,
+    };
+    let bytes = transfer.as_bytes();
+    assert_eq!(bytes[0], 1);
+    assert_eq!(Message::from_bytes(bytes), transfer);
+
+    let memo = Message::Memo(7, Bytes::from_bytes(*b"memo"));
+    let bytes = memo.as_bytes();
+    assert_eq!(bytes[0], 2);
+    assert_eq!(&bytes[1..9], &[7, 0, 0, 0, b'm', b'e', b'm', b'o']);
+    assert!(bytes[9..].iter().all(|&b| b == 0));
+    assert_eq!(Message::from_bytes(bytes), memo);
+
+    let mut invalid = [0u8; 41];
+    invalid[0] = 3;
+    assert!(matches!(
+        Message::from_slice(&invalid),
+        Err(Error::InvalidDiscriminant(3))
+    ));
+
+    assert_eq!(Tagged::SIZE, 2 + 2);
+    assert_eq!(Tagged::zero(), Tagged::First);
+    assert_eq!(Wrapper::zero().tagged, Tagged::First);
+    assert_eq!(Tagged::First.as_bytes(), [0x00, 0x01, 0, 0]);
+    assert_eq!(Tagged::Second(9).as_bytes(), [0x01, 0x01, 9, 0]);
+    assert_eq!(Tagged::Third { value: 0x0302 }.as_bytes(), [7, 0, 2, 3]);
+    assert_eq!(Tagged::from_slice(&[7, 0, 2, 3]).unwrap(), Tagged::Third { value: 0x0302 });
+}
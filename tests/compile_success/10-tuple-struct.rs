@@ -0,0 +1,16 @@
+use mucodec::{Bytes, ReprBytes, ReprSize};
+
+#[derive(Debug, Default, PartialEq, ReprBytes)]
+pub struct Pair(Bytes<32>, u64, u16);
+
+fn main() {
+    assert_eq!(Pair::SIZE, 32 + 8 + 2);
+
+    let pair = Pair(Bytes::from_bytes([7u8; 32]), 0x0102, 0x0304);
+    let bytes = pair.as_bytes();
+    assert_eq!(&bytes[..32], &[7u8; 32]);
+    assert_eq!(&bytes[32..40], &[0x02, 0x01, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(&bytes[40..], &[0x04, 0x03]);
+    assert_eq!(Pair::from_bytes(bytes), pair);
+    assert_eq!(Pair::zero(), Pair::default());
+}
@@ -0,0 +1,50 @@
+use mucodec::{Error, ListU16, ReprBytes};
+
+#[derive(Debug, Default, PartialEq, ReprBytes)]
+pub struct Packet {
+    id: u32,
+    values: ListU16<4>,
+}
+
+#[derive(Debug, Default, PartialEq, ReprBytes)]
+pub enum Frame {
+    #[default]
+    Empty,
+    Data(Packet),
+}
+
+fn main() {
+    let packet = Packet {
+        id: 1,
+        values: ListU16::<4>::from_bytes([16, 1, 0x80, 2, 0, 3, 0, 4, 0]),
+    };
+    let bytes = packet.as_bytes();
+    assert_eq!(Packet::try_from_bytes(bytes).unwrap(), packet);
+
+    // Corrupt the bit width of the nested list
+    let mut invalid = bytes;
+    invalid[4] = 17;
+    assert!(matches!(
+        Packet::try_from_bytes(invalid),
+        Err(Error::InvalidBitWidth(17))
+    ));
+    assert!(Packet::from_slice(&invalid).is_err());
+
+    let frame = Frame::Data(packet);
+    let bytes = frame.as_bytes();
+    assert_eq!(Frame::try_from_bytes(bytes).unwrap(), frame);
+
+    let mut invalid = bytes;
+    invalid[5] = 17;
+    assert!(matches!(
+        Frame::try_from_bytes(invalid),
+        Err(Error::InvalidBitWidth(17))
+    ));
+
+    let mut invalid = bytes;
+    invalid[0] = 9;
+    assert!(matches!(
+        Frame::try_from_bytes(invalid),
+        Err(Error::InvalidDiscriminant(9))
+    ));
+}
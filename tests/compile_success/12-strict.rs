@@ -0,0 +1,48 @@
+use mucodec::{Error, ReprBytes, String};
+
+#[derive(Debug, Default, PartialEq, ReprBytes)]
+pub struct Account {
+    id: u32,
+    name: String<8>,
+}
+
+#[derive(Debug, Default, PartialEq, ReprBytes)]
+pub enum Event {
+    #[default]
+    Closed,
+    Opened(Account),
+}
+
+fn main() {
+    let mut name = [0u8; 8];
+    name[..5].copy_from_slice(b"alice");
+    let account = Account {
+        id: 7,
+        name: String::from_bytes(name),
+    };
+
+    let bytes = account.as_bytes();
+    assert_eq!(Account::from_bytes_strict(bytes).unwrap(), account);
+
+    // Garbage after the NUL terminator of a nested string
+    let mut invalid = bytes;
+    invalid[11] = b'!';
+    assert!(Account::try_from_bytes(invalid).is_ok());
+    assert!(matches!(
+        Account::from_bytes_strict(invalid),
+        Err(Error::NonCanonical)
+    ));
+
+    let event = Event::Opened(account);
+    let bytes = event.as_bytes();
+    assert_eq!(Event::from_slice_strict(&bytes).unwrap(), event);
+
+    // Non-zero padding after a variant without fields
+    let mut invalid = Event::Closed.as_bytes();
+    invalid[1] = 1;
+    assert_eq!(Event::from_bytes(invalid), Event::Closed);
+    assert!(matches!(
+        Event::from_bytes_strict(invalid),
+        Err(Error::NonCanonical)
+    ));
+}
@@ -0,0 +1,21 @@
+use mucodec::{Bytes, ReprBase64, ReprBytes, ReprHex};
+
+#[derive(Debug, Default, PartialEq, ReprBytes)]
+pub struct Message {
+    id: u16,
+    payload: Bytes<3>,
+}
+
+fn main() {
+    let message = Message {
+        id: 0x0201,
+        payload: Bytes::from_bytes([3, 4, 5]),
+    };
+
+    assert_eq!(message.to_hex(), "0102030405");
+    assert_eq!(Message::from_hex("0102030405").unwrap(), message);
+    assert_eq!(message.to_base64(), "AQIDBAU=");
+    assert_eq!(Message::from_base64("AQIDBAU=").unwrap(), message);
+    assert_eq!(Message::HEX_SIZE, 10);
+    assert_eq!(Message::BASE64_SIZE, 8);
+}
@@ -0,0 +1,76 @@
+use mucodec::{Error, ReprPacked};
+
+#[derive(Debug, PartialEq, ReprPacked)]
+pub struct Config {
+    #[mucodec(bits = 3)]
+    mode: u8,
+    #[mucodec(bits = 1)]
+    enabled: bool,
+    #[mucodec(bits = 12)]
+    timeout: u16,
+    retries: u8,
+    backoff: u16,
+    offset: i16,
+}
+
+#[derive(Debug, PartialEq, ReprPacked)]
+pub struct Flags(
+    #[mucodec(bits = 1)] bool,
+    #[mucodec(bits = 1)] bool,
+    #[mucodec(bits = 6)] u8,
+);
+
+fn main() {
+    let config = Config {
+        mode: 5,
+        enabled: true,
+        timeout: 3000,
+        retries: 3,
+        backoff: 100,
+        offset: -2,
+    };
+
+    // The fields without declared bits all take the 7 bits needed by `backoff`
+    let (bit_width, packed) = config.pack();
+    assert_eq!(bit_width, 7);
+    assert_eq!(packed.len(), (3 + 1 + 12 + 3 * 7usize).div_ceil(8));
+    assert_eq!(Config::unpack_strict(bit_width, &packed).unwrap(), config);
+
+    // Headers wider than every field without declared bits
+    assert!(matches!(
+        Config::unpack(17, &[0; 9]),
+        Err(Error::InvalidBitWidth(17))
+    ));
+
+    // Values that do not fit in their field
+    assert!(matches!(
+        Config::unpack(9, &[0xFF; 6]),
+        Err(Error::InvalidBitWidth(9))
+    ));
+    assert!(matches!(
+        Config::unpack(7, &packed[1..]),
+        Err(Error::InvalidDataSize { .. })
+    ));
+
+    // Structs with only declared widths have a zero header
+    let flags = Flags(true, false, 33);
+    assert_eq!(flags.pack(), (0, vec![0b1000_0101]));
+    assert_eq!(Flags::unpack(0, &[0b1000_0101]).unwrap(), flags);
+    assert!(Flags::unpack(1, &[0b1000_0101]).is_err());
+    assert!(matches!(
+        Flags::unpack(0, &[0b10]),
+        Ok(Flags(false, true, 0))
+    ));
+
+    // Values wider than their declared bits can not be packed
+    assert_eq!(flags.try_pack().unwrap(), flags.pack());
+    assert!(matches!(
+        Flags(true, true, 64).try_pack(),
+        Err(Error::BitOverflow {
+            needed: 7,
+            available: 6
+        })
+    ));
+    std::panic::set_hook(Box::new(|_| {}));
+    assert!(std::panic::catch_unwind(|| Flags(true, true, 64).pack()).is_err());
+}
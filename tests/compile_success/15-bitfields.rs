@@ -0,0 +1,86 @@
+use mucodec::{Error, ReprBytes, ReprSize};
+
+#[derive(Debug, Default, PartialEq, ReprBytes)]
+pub struct Header {
+    #[mucodec(bits = 4)]
+    version: u8,
+    #[mucodec(bits = 3)]
+    kind: u8,
+    #[mucodec(bits = 1)]
+    urgent: bool,
+    length: u16,
+    #[mucodec(bits = 12)]
+    window: u16,
+    #[mucodec(bits = 1)]
+    last: bool,
+}
+
+#[derive(Debug, Default, PartialEq, ReprBytes)]
+pub struct Status(#[mucodec(bits = 2)] u8, #[mucodec(bits = 5)] i8);
+
+/// The generated code does not depend on the names in scope where the struct is defined.
+mod shadowed {
+    use mucodec::ReprBytes;
+
+    #[allow(dead_code)]
+    trait Default {}
+
+    #[derive(Debug, ::core::default::Default, PartialEq, ReprBytes)]
+    pub struct Register {
+        #[mucodec(bits = 3)]
+        pub mode: u8,
+        #[mucodec(bits = 5)]
+        pub level: u8,
+    }
+}
+
+fn main() {
+    // 8 bits in one byte, a u16, and 13 bits in two bytes
+    assert_eq!(Header::SIZE, 1 + 2 + 2);
+
+    let header = Header {
+        version: 4,
+        kind: 5,
+        urgent: true,
+        length: 1500,
+        window: 0xABC,
+        last: true,
+    };
+    let bytes = header.as_bytes();
+    assert_eq!(bytes, [0xD4, 0xDC, 0x05, 0xBC, 0x1A]);
+    assert_eq!(Header::from_bytes_strict(bytes).unwrap(), header);
+    assert_eq!(Header::zero(), Header::default());
+
+    // The top 3 bits of the last byte are reserved
+    let mut invalid = bytes;
+    invalid[4] |= 0x20;
+    assert_eq!(Header::from_bytes(invalid), header);
+    assert!(matches!(
+        Header::try_from_bytes(invalid),
+        Err(Error::NonZeroReserved)
+    ));
+
+    assert_eq!(shadowed::Register::zero(), shadowed::Register::default());
+
+    // Signed bitfields are stored in two's complement, like in C
+    assert_eq!(Status::SIZE, 1);
+    let status = Status(3, -9);
+    assert_eq!(status.as_bytes(), [0b0101_1111]);
+    assert_eq!(Status::try_from_bytes([0b0101_1111]).unwrap(), status);
+    assert_eq!(Status::from_bytes_strict([0b0111_1100]).unwrap(), Status(0, -1));
+    assert_eq!(Status::from_bytes_strict([0b0100_0000]).unwrap(), Status(0, -16));
+    assert!(Status::try_from_bytes([0x80]).is_err());
+
+    // Values wider than their declared bits have no encoding
+    let wide = Status(0, 16);
+    assert_eq!(Status(0, -16).try_as_bytes().unwrap(), [0b0100_0000]);
+    assert!(matches!(
+        wide.try_as_bytes(),
+        Err(Error::BitOverflow {
+            needed: 6,
+            available: 5
+        })
+    ));
+    std::panic::set_hook(Box::new(|_| {}));
+    assert!(std::panic::catch_unwind(|| wide.as_bytes()).is_err());
+}
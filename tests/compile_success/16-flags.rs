@@ -0,0 +1,58 @@
+use mucodec::{Error, Flags, ReprBytes, ReprFlags};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ReprFlags)]
+pub enum Permission {
+    Read,
+    Write,
+    #[mucodec(bit = 7)]
+    Admin,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ReprFlags)]
+pub enum Feature {
+    Compression,
+    #[mucodec(bit = 20)]
+    Encryption,
+    Signing,
+}
+
+#[derive(Debug, Default, PartialEq, ReprBytes)]
+pub struct User {
+    id: u32,
+    permissions: Flags<Permission>,
+    features: Flags<Feature>,
+}
+
+fn main() {
+    assert_eq!(<Flags<Permission> as mucodec::ReprSize>::SIZE, 1);
+    assert_eq!(<Flags<Feature> as mucodec::ReprSize>::SIZE, 4);
+    assert_eq!(Feature::Signing.bit(), 21);
+
+    let permissions = Permission::Read | Permission::Admin;
+    assert!(permissions.contains(Permission::Admin));
+    assert!(!permissions.contains(Permission::Write));
+    assert_eq!(permissions.as_bytes(), [0b1000_0001]);
+    assert_eq!(format!("{permissions:?}"), "Flags(Read | Admin)");
+    assert_eq!(!permissions, Permission::Write.into());
+
+    // Unknown bits are dropped, or rejected by strict decoding
+    assert_eq!(Flags::<Permission>::from_bytes([0xFF]), Flags::all());
+    assert!(matches!(
+        Flags::<Permission>::from_bytes_strict([0xFF]),
+        Err(Error::NonCanonical)
+    ));
+
+    let user = User {
+        id: 1,
+        permissions,
+        features: Feature::Encryption | Feature::Signing,
+    };
+    let bytes = user.as_bytes();
+    assert_eq!(bytes, [1, 0, 0, 0, 0b1000_0001, 0, 0, 0x30, 0]);
+    assert_eq!(User::from_bytes_strict(bytes).unwrap(), user);
+
+    let mut invalid = bytes;
+    invalid[5] = 0x02;
+    assert_eq!(User::from_bytes(invalid), user);
+    assert!(User::from_bytes_strict(invalid).is_err());
+}
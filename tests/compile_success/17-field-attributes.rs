@@ -0,0 +1,153 @@
+use std::time::Duration;
+
+use mucodec::{Error, ReprBytes, ReprPacked, ReprSize};
+
+/// Encodes a type from another crate, which can not implement `ReprBytes` itself, as whole
+/// seconds.
+mod seconds {
+    use std::time::Duration;
+
+    use mucodec::Error;
+
+    pub const SIZE: usize = 4;
+
+    pub fn encode(value: &Duration) -> [u8; SIZE] {
+        (value.as_secs() as u32).to_le_bytes()
+    }
+
+    pub fn decode(input: [u8; SIZE]) -> Result<Duration, Error> {
+        Ok(Duration::from_secs(u32::from_le_bytes(input).into()))
+    }
+}
+
+/// Decodes any non-zero byte as `true`.
+mod lenient_bool {
+    use mucodec::Error;
+
+    pub const SIZE: usize = 1;
+
+    pub fn encode(value: &bool) -> [u8; SIZE] {
+        [*value as u8]
+    }
+
+    pub fn decode(input: [u8; SIZE]) -> Result<bool, Error> {
+        Ok(input[0] != 0)
+    }
+}
+
+/// Rejects zero, which is not a port that can be listened on.
+mod port {
+    use mucodec::Error;
+
+    pub const SIZE: usize = 2;
+
+    pub fn encode(value: &u16) -> [u8; SIZE] {
+        value.to_le_bytes()
+    }
+
+    pub fn decode(input: [u8; SIZE]) -> Result<u16, Error> {
+        match u16::from_le_bytes(input) {
+            0 => Err(Error::InvalidField {
+                field: "port",
+                reason: "port must not be zero".into(),
+            }),
+            port => Ok(port),
+        }
+    }
+}
+
+fn default_score() -> u32 {
+    100
+}
+
+#[derive(Debug, PartialEq, ReprBytes)]
+pub struct Peer {
+    #[mucodec(with = seconds)]
+    uptime: Duration,
+    port: u16,
+    #[mucodec(skip)]
+    connections: Vec<u32>,
+    #[mucodec(with = lenient_bool)]
+    trusted: bool,
+    #[mucodec(skip, default = default_score)]
+    score: u32,
+}
+
+impl Default for Peer {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+#[derive(Debug, PartialEq, ReprBytes)]
+pub struct Listener {
+    #[mucodec(with = port)]
+    port: u16,
+}
+
+impl Default for Listener {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+#[derive(Debug, PartialEq, ReprPacked)]
+pub struct Window {
+    start: u32,
+    end: u32,
+    #[mucodec(skip)]
+    cached_len: Option<u32>,
+}
+
+fn main() {
+    assert_eq!(Peer::SIZE, 7);
+
+    let peer = Peer {
+        uptime: Duration::from_secs(3600),
+        port: 8080,
+        connections: vec![1, 2, 3],
+        trusted: true,
+        score: 7,
+    };
+
+    let bytes = peer.as_bytes();
+    assert_eq!(bytes, [0x10, 0x0E, 0, 0, 0x90, 0x1F, 1]);
+
+    // Skipped fields are filled with their default
+    let decoded = Peer::from_bytes_strict(bytes).unwrap();
+    assert_eq!(decoded.uptime, peer.uptime);
+    assert!(decoded.connections.is_empty());
+    assert_eq!(decoded.score, 100);
+    assert_eq!(Peer::zero().uptime, Duration::ZERO);
+    assert_eq!(Peer::zero().score, 100);
+
+    // Strict decoding checks that modules encode values back to the same bytes
+    let mut lenient = bytes;
+    lenient[6] = 2;
+    assert!(Peer::from_bytes(lenient).trusted);
+    assert!(Peer::try_from_bytes(lenient).is_ok());
+    assert!(matches!(
+        Peer::from_bytes_strict(lenient),
+        Err(Error::NonCanonical)
+    ));
+
+    // Fields encoded with a module start from their default, even if zeroed bytes do not decode
+    assert_eq!(Listener::zero().port, 0);
+    assert!(Listener::try_from_bytes([0, 0]).is_err());
+
+    let window = Window {
+        start: 10,
+        end: 20,
+        cached_len: Some(10),
+    };
+    let (bit_width, packed) = window.pack();
+    assert_eq!(bit_width, 5);
+    assert_eq!(packed.len(), 2);
+    assert_eq!(
+        Window::unpack(bit_width, &packed).unwrap(),
+        Window {
+            cached_len: None,
+            ..window
+        }
+    );
+}
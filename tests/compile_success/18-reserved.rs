@@ -0,0 +1,41 @@
+use mucodec::{Error, ReprBytes, ReprSize};
+
+#[derive(Debug, Default, PartialEq, ReprBytes)]
+#[mucodec(reserved = 4)]
+pub struct Record {
+    kind: u8,
+    #[mucodec(reserved = 3)]
+    length: u32,
+    #[mucodec(bits = 4)]
+    version: u8,
+    #[mucodec(reserved = 1, bits = 4)]
+    flags: u8,
+}
+
+fn main() {
+    assert_eq!(Record::SIZE, 1 + 3 + 4 + 1 + 1 + 1 + 4);
+
+    let record = Record {
+        kind: 1,
+        length: 0x0403_0201,
+        version: 2,
+        flags: 0xF,
+    };
+    let bytes = record.as_bytes();
+    assert_eq!(bytes, [1, 0, 0, 0, 1, 2, 3, 4, 2, 0, 0xF, 0, 0, 0, 0]);
+    assert_eq!(Record::from_bytes_strict(bytes).unwrap(), record);
+    assert_eq!(Record::zero().as_bytes(), [0; 15]);
+
+    // Reserved bytes are ignored, except by strict decoding
+    for offset in [1, 3, 9, 11, 14] {
+        let mut reserved = bytes;
+        reserved[offset] = 0xAA;
+
+        assert_eq!(Record::from_bytes(reserved), record);
+        assert_eq!(Record::try_from_bytes(reserved).unwrap(), record);
+        assert!(matches!(
+            Record::from_bytes_strict(reserved),
+            Err(Error::NonZeroReserved)
+        ));
+    }
+}
@@ -0,0 +1,102 @@
+use mucodec::{Error, ReprBytes, ReprPacked, String};
+
+fn non_zero(amount: &u64) -> Result<(), &'static str> {
+    match amount {
+        0 => Err("must not be zero"),
+        _ => Ok(()),
+    }
+}
+
+fn ascii<const N: usize>(value: &String<N>) -> Result<(), std::string::String> {
+    match value.as_bytes().iter().find(|byte| !byte.is_ascii()) {
+        Some(byte) => Err(format!("byte {byte:#x} is not ascii")),
+        None => Ok(()),
+    }
+}
+
+fn ordered(transfer: &Transfer) -> Result<(), &'static str> {
+    match transfer.from < transfer.to {
+        true => Ok(()),
+        false => Err("accounts must be ordered"),
+    }
+}
+
+#[derive(Debug, Default, PartialEq, ReprBytes)]
+#[mucodec(validate = ordered)]
+pub struct Transfer {
+    from: u32,
+    to: u32,
+    #[mucodec(validate = non_zero)]
+    amount: u64,
+    #[mucodec(validate = ascii)]
+    memo: String<4>,
+}
+
+fn increasing(range: &Range) -> Result<(), &'static str> {
+    match range.0 <= range.1 {
+        true => Ok(()),
+        false => Err("start is after end"),
+    }
+}
+
+#[derive(Debug, PartialEq, ReprPacked)]
+#[mucodec(validate = increasing)]
+pub struct Range(u64, #[mucodec(validate = non_zero)] u64);
+
+fn main() {
+    let transfer = Transfer {
+        from: 1,
+        to: 2,
+        amount: 100,
+        memo: String::from_bytes(*b"rent"),
+    };
+    let bytes = transfer.as_bytes();
+    assert_eq!(Transfer::try_from_bytes(bytes).unwrap(), transfer);
+    assert_eq!(Transfer::from_bytes_strict(bytes).unwrap(), transfer);
+
+    let mut zero = bytes;
+    zero[8..16].fill(0);
+    match Transfer::try_from_bytes(zero) {
+        Err(Error::InvalidField { field, reason }) => {
+            assert_eq!(field, "amount");
+            assert_eq!(reason, "must not be zero");
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+
+    // The infallible path does not run validators
+    assert_eq!(Transfer::from_bytes(zero).amount, 0);
+
+    let mut memo = bytes;
+    memo[16] = 0xFF;
+    assert!(matches!(
+        Transfer::from_bytes_strict(memo),
+        Err(Error::InvalidField { field: "memo", .. })
+    ));
+
+    // Fields are validated before the struct
+    let mut swapped = zero;
+    swapped[..4].copy_from_slice(&3u32.to_le_bytes());
+    assert!(matches!(
+        Transfer::try_from_bytes(swapped),
+        Err(Error::InvalidField { field: "amount", .. })
+    ));
+    swapped[8] = 1;
+    let error = Transfer::try_from_bytes(swapped).unwrap_err();
+    assert_eq!(error.to_string(), "Invalid Transfer: accounts must be ordered");
+
+    let (bit_width, packed) = Range(1, 2).pack();
+    assert_eq!(Range::unpack(bit_width, &packed).unwrap(), Range(1, 2));
+
+    let (bit_width, packed) = Range(2, 1).pack();
+    assert!(matches!(
+        Range::unpack(bit_width, &packed),
+        Err(Error::InvalidField { field: "Range", .. })
+    ));
+
+    let (bit_width, packed) = Range(0, 0).pack();
+    assert!(matches!(
+        Range::unpack(bit_width, &packed),
+        Err(Error::InvalidField { field: "1", .. })
+    ));
+}
@@ -0,0 +1,80 @@
+use mucodec::{Be, Bytes, Le, ReprBytes, ReprSize};
+
+#[derive(Debug, Default, PartialEq, ReprBytes)]
+#[mucodec(endian = "big")]
+pub struct Key {
+    table: u16,
+    id: u64,
+    #[mucodec(endian = "little")]
+    version: u32,
+    hash: Bytes<2>,
+}
+
+type Amount = u64;
+
+/// Integers named by path follow the struct's byte order, and aliases have to set their own.
+#[derive(Debug, Default, PartialEq, ReprBytes)]
+#[mucodec(endian = "big")]
+pub struct Transfer {
+    source: core::primitive::u32,
+    target: std::primitive::u16,
+    #[mucodec(endian = "big")]
+    amount: Amount,
+    #[mucodec(endian = "little")]
+    fee: Amount,
+}
+
+#[derive(Debug, Default, PartialEq, ReprBytes)]
+pub struct Packet {
+    #[mucodec(endian = "big")]
+    length: u16,
+    checksum: u16,
+    sequence: Be<u32>,
+    timestamp: Le<u32>,
+}
+
+fn main() {
+    assert_eq!(Key::SIZE, 16);
+
+    let key = Key {
+        table: 1,
+        id: 0x0102_0304,
+        version: 7,
+        hash: Bytes::from_bytes([0xAB, 0xCD]),
+    };
+    let bytes = key.as_bytes();
+    assert_eq!(
+        bytes,
+        [0, 1, 0, 0, 0, 0, 1, 2, 3, 4, 7, 0, 0, 0, 0xAB, 0xCD]
+    );
+    assert_eq!(Key::from_bytes_strict(bytes).unwrap(), key);
+    assert_eq!(Key::zero(), Key::default());
+
+    // Big endian keys sort like their integers
+    let next = Key { id: 0x0102_0305, ..Key::default() };
+    assert!(next.as_bytes() > Key { id: 0x0102_0304, ..Key::default() }.as_bytes());
+
+    let transfer = Transfer {
+        source: 0x0102_0304,
+        target: 0x0506,
+        amount: 0x0708,
+        fee: 9,
+    };
+    let bytes = transfer.as_bytes();
+    assert_eq!(
+        bytes,
+        [1, 2, 3, 4, 5, 6, 0, 0, 0, 0, 0, 0, 7, 8, 9, 0, 0, 0, 0, 0, 0, 0]
+    );
+    assert_eq!(Transfer::from_bytes_strict(bytes).unwrap(), transfer);
+
+    let packet = Packet {
+        length: 0x0102,
+        checksum: 0x0102,
+        sequence: Be(0x0102_0304),
+        timestamp: Le(0x0102_0304),
+    };
+    let bytes = packet.as_bytes();
+    assert_eq!(bytes, [1, 2, 2, 1, 1, 2, 3, 4, 4, 3, 2, 1]);
+    assert_eq!(Packet::try_from_bytes(bytes).unwrap(), packet);
+    assert_eq!(*packet.sequence + 1, 0x0102_0305);
+}
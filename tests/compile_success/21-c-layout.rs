@@ -0,0 +1,85 @@
+use mucodec::{Be, Error, ReprBytes, ReprSize};
+
+// struct reading {
+//     uint8_t  kind;     // offset 0
+//                        // 3 bytes of padding
+//     uint32_t value;    // offset 4
+//     uint16_t sensor;   // offset 8
+//                        // 6 bytes of padding
+//     uint64_t time;     // offset 16
+//     uint8_t  flags;    // offset 24
+//                        // 7 bytes of padding
+// };
+//
+// Nested structs are embedded with their in-memory size, so they need `#[repr(C)]` too.
+#[derive(Debug, Default, PartialEq, ReprBytes)]
+#[mucodec(layout = "c")]
+#[repr(C)]
+pub struct Reading {
+    kind: u8,
+    value: u32,
+    sensor: u16,
+    time: u64,
+    flags: u8,
+}
+
+#[derive(Debug, Default, PartialEq, ReprBytes)]
+#[mucodec(layout = "c", reserved = 1)]
+pub struct Frame {
+    length: Be<u16>,
+    #[mucodec(reserved = 1)]
+    reading: Reading,
+    #[mucodec(skip)]
+    received: bool,
+}
+
+fn main() {
+    assert_eq!(Reading::SIZE, core::mem::size_of::<Reading>());
+    assert_eq!(Reading::SIZE, 32);
+
+    let reading = Reading {
+        kind: 1,
+        value: 2,
+        sensor: 3,
+        time: 4,
+        flags: 5,
+    };
+    let bytes = reading.as_bytes();
+    assert_eq!(bytes[0], 1);
+    assert_eq!(bytes[4..8], 2u32.to_le_bytes());
+    assert_eq!(bytes[8..10], 3u16.to_le_bytes());
+    assert_eq!(bytes[16..24], 4u64.to_le_bytes());
+    assert_eq!(bytes[24], 5);
+    assert_eq!(Reading::from_bytes_strict(bytes).unwrap(), reading);
+
+    // Padding is reserved, so only strict decoding rejects non-zero bytes in it
+    let mut padded = bytes;
+    padded[1] = 0xFF;
+    padded[31] = 0xFF;
+    assert_eq!(Reading::from_bytes(padded), reading);
+    assert!(matches!(
+        Reading::from_bytes_strict(padded),
+        Err(Error::NonZeroReserved)
+    ));
+
+    // length at 0, a reserved byte at 2, the nested struct aligned to 8, and a trailing reserved
+    // byte padded to the alignment of the struct
+    assert_eq!(Frame::SIZE, 8 + 32 + 8);
+
+    let frame = Frame {
+        length: Be(32),
+        reading,
+        received: true,
+    };
+    let bytes = frame.as_bytes();
+    assert_eq!(bytes[..8], [0, 32, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(bytes[8..40], frame.reading.as_bytes());
+    assert_eq!(bytes[40..], [0; 8]);
+    assert_eq!(
+        Frame::from_bytes_strict(bytes).unwrap(),
+        Frame {
+            received: false,
+            ..frame
+        }
+    );
+}